@@ -0,0 +1,267 @@
+use crate::{ArrayLayout, Endian};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// 遍历数组元素的顺序。
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Order<'a> {
+    /// 按端序遍历，大端序最后一维变化最快，小端序第一维变化最快。
+    Endian(Endian),
+    /// 按维度排列遍历，排列从最外层循环到最内层循环。
+    Perm(&'a [usize]),
+}
+
+impl From<Endian> for Order<'_> {
+    #[inline]
+    fn from(value: Endian) -> Self {
+        Self::Endian(value)
+    }
+}
+
+impl<'a> From<&'a [usize]> for Order<'a> {
+    #[inline]
+    fn from(value: &'a [usize]) -> Self {
+        Self::Perm(value)
+    }
+}
+
+impl<'a, const M: usize> From<&'a [usize; M]> for Order<'a> {
+    #[inline]
+    fn from(value: &'a [usize; M]) -> Self {
+        Self::Perm(value)
+    }
+}
+
+/// 遍历数组时的缓存行和页足迹。
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Footprint {
+    /// 缓存行访问次数，跨越缓存行的元素计为多次访问。
+    pub accesses: usize,
+    /// 访问到的不同缓存行数量。
+    pub lines: usize,
+    /// 访问到的不同页数量，页大小为 [`Footprint::PAGE_SIZE`]。
+    pub pages: usize,
+    /// 复用距离直方图。
+    ///
+    /// 复用距离是两次访问同一缓存行之间访问过的不同缓存行数量，
+    /// 键为复用距离，值为该距离出现的次数。
+    pub reuse: BTreeMap<usize, usize>,
+}
+
+impl Footprint {
+    /// 估计页数时使用的页大小（字节）。
+    pub const PAGE_SIZE: usize = 4096;
+
+    /// 估计在容量为 `capacity` 个缓存行的全相联 LRU 缓存上的缺失次数。
+    pub fn misses(&self, capacity: usize) -> usize {
+        self.lines + self.reuse.range(capacity..).map(|(_, n)| n).sum::<usize>()
+    }
+
+    /// 平均复用距离，没有复用时返回 `None`。
+    pub fn mean_reuse_distance(&self) -> Option<f64> {
+        let (sum, n) = self
+            .reuse
+            .iter()
+            .fold((0, 0), |(sum, n), (&d, &c)| (sum + d * c, n + c));
+        (n > 0).then(|| sum as f64 / n as f64)
+    }
+}
+
+impl<const N: usize> ArrayLayout<N> {
+    /// 估计按 `order` 遍历数组时访问的缓存行和页，以及缓存行的复用距离。
+    ///
+    /// 布局的步长和偏移以字节为单位。
+    /// 遍历顺序合并后是单个等步长的维度时解析计算，否则逐元素模拟。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, Endian::{BigEndian, LittleEndian}};
+    /// let layout = ArrayLayout::<2>::new_contiguous(&[64, 64], BigEndian, 4);
+    ///
+    /// let rows = layout.footprint(4, 64, BigEndian);
+    /// let cols = layout.footprint(4, 64, LittleEndian);
+    /// assert_eq!(rows.lines, 256);
+    /// assert_eq!(cols.lines, 256);
+    /// assert_eq!(rows.pages, 4);
+    /// // 按行遍历在 16 行的缓存上只有冷缺失，按列遍历则每次都缺失
+    /// assert_eq!(rows.misses(16), 256);
+    /// assert_eq!(cols.misses(16), 64 * 64);
+    /// ```
+    pub fn footprint<'a>(
+        &self,
+        element_size: usize,
+        line_size: usize,
+        order: impl Into<Order<'a>>,
+    ) -> Footprint {
+        let traversal = self.traversal(order.into());
+        traversal
+            .footprint_analytic(element_size, line_size)
+            .unwrap_or_else(|| traversal.footprint_simulated(element_size, line_size))
+    }
+
+    /// 按遍历顺序转置，使得大端序遍历结果布局等价于按 `order` 遍历原布局。
+    fn traversal(&self, order: Order) -> Self {
+        let perm = match order {
+            Order::Endian(Endian::BigEndian) => return self.clone(),
+            Order::Endian(Endian::LittleEndian) => (0..self.ndim).rev().collect(),
+            Order::Perm(perm) => perm.to_vec(),
+        };
+        let mut sorted = perm.clone();
+        sorted.sort_unstable();
+        assert!(
+            sorted.iter().copied().eq(0..self.ndim),
+            "{perm:?} is not a permutation of {} axes",
+            self.ndim
+        );
+        self.transpose(&perm)
+    }
+
+    fn footprint_analytic(&self, element_size: usize, line_size: usize) -> Option<Footprint> {
+        const PAGE: isize = Footprint::PAGE_SIZE as _;
+
+        let n = self.num_elements();
+        if n == 0 {
+            return Some(Footprint {
+                accesses: 0,
+                lines: 0,
+                pages: 0,
+                reuse: BTreeMap::new(),
+            });
+        }
+
+        let merged = self.merge_be(0, self.ndim).unwrap_or_else(|| self.clone());
+        let s = match merged.strides() {
+            [] => 0,
+            &[s] => s,
+            [..] => return None,
+        };
+
+        // 所有元素都不跨越缓存行和页
+        let es = element_size.max(1) as isize;
+        let line = line_size as isize;
+        if line % es != 0 || PAGE % line != 0 || merged.offset() % es != 0 || s % es != 0 {
+            return None;
+        }
+
+        let range = merged.data_range();
+        let span = |unit: isize| {
+            (range.end() + es - 1).div_euclid(unit) - range.start().div_euclid(unit) + 1
+        };
+        // 步长不超过一个单位时跨度内的每个单位都会被访问，否则每个元素访问不同的单位
+        let count = |unit: isize| {
+            if s.abs() <= unit {
+                span(unit) as usize
+            } else {
+                n
+            }
+        };
+
+        let lines = count(line);
+        let mut reuse = BTreeMap::new();
+        if n > lines {
+            reuse.insert(0, n - lines);
+        }
+        Some(Footprint {
+            accesses: n,
+            lines,
+            pages: count(PAGE),
+            reuse,
+        })
+    }
+
+    fn footprint_simulated(&self, element_size: usize, line_size: usize) -> Footprint {
+        let n = self.num_elements();
+        let es = element_size.max(1) as isize;
+        let line = line_size as isize;
+        let page = Footprint::PAGE_SIZE as isize;
+        let max_lines = (es as usize - 1) / line_size + 2;
+
+        // 在树状数组中标记每个缓存行最后一次被访问的时刻，两次访问之间的标记数即复用距离
+        let mut tree = Fenwick(vec![0; n * max_lines + 1]);
+        let mut last = HashMap::new();
+        let mut pages = HashSet::new();
+        let mut reuse = BTreeMap::new();
+        let mut time = 0;
+        for i in 0..n {
+            let addr = self.element_offset(i, Endian::BigEndian);
+            let end = addr + es - 1;
+            pages.extend(addr.div_euclid(page)..=end.div_euclid(page));
+            for l in addr.div_euclid(line)..=end.div_euclid(line) {
+                time += 1;
+                if let Some(prev) = last.insert(l, time) {
+                    let distance = tree.sum(time) - tree.sum(prev);
+                    *reuse.entry(distance).or_insert(0) += 1;
+                    tree.add(prev, -1);
+                }
+                tree.add(time, 1);
+            }
+        }
+
+        Footprint {
+            accesses: time,
+            lines: last.len(),
+            pages: pages.len(),
+            reuse,
+        }
+    }
+}
+
+struct Fenwick(Vec<isize>);
+
+impl Fenwick {
+    fn add(&mut self, mut i: usize, val: isize) {
+        while i < self.0.len() {
+            self.0[i] += val;
+            i += i & i.wrapping_neg()
+        }
+    }
+
+    fn sum(&self, mut i: usize) -> usize {
+        let mut ans = 0;
+        while i > 0 {
+            ans += self.0[i];
+            i -= i & i.wrapping_neg()
+        }
+        ans as _
+    }
+}
+
+#[test]
+fn test() {
+    let contiguous = ArrayLayout::<3>::new_contiguous(&[4, 6, 8], Endian::BigEndian, 4);
+    let layouts = [
+        contiguous.clone(),
+        contiguous.transpose(&[2, 0, 1]),
+        contiguous.slice(2, 7, -1, 8),
+        contiguous.slice(1, 0, 2, 3),
+        contiguous.index(2, 3).slice(1, 0, 1, 1).broadcast(1, 5),
+        ArrayLayout::<3>::new(&[5, 1, 3], &[4096, 0, 0], 64),
+        ArrayLayout::<3>::new(&[3, 9], &[256, 128], 0),
+        ArrayLayout::<3>::new(&[7, 9], &[6, 2], 1),
+    ];
+    for layout in &layouts {
+        for order in [
+            Order::Endian(Endian::BigEndian),
+            Order::Endian(Endian::LittleEndian),
+        ] {
+            let traversal = layout.traversal(order);
+            if let Some(analytic) = traversal.footprint_analytic(4, 64) {
+                assert_eq!(analytic, traversal.footprint_simulated(4, 64), "{order:?}");
+            }
+        }
+    }
+
+    // 跨越缓存行的元素
+    let layout = ArrayLayout::<1>::new(&[4], &[6], 60);
+    let footprint = layout.footprint(8, 64, Endian::BigEndian);
+    assert_eq!(footprint.accesses, 5);
+    assert_eq!(footprint.lines, 2);
+    assert_eq!(footprint.reuse, BTreeMap::from([(0, 3)]));
+
+    // 交替访问两个缓存行
+    let layout = ArrayLayout::<2>::new(&[2, 4], &[4, 64], 0);
+    let footprint = layout.footprint(4, 64, &[0, 1]);
+    assert_eq!(footprint.lines, 4);
+    assert_eq!(footprint.reuse, BTreeMap::from([(3, 4)]));
+    assert_eq!(footprint.misses(4), 4);
+    assert_eq!(footprint.misses(3), 8);
+    assert_eq!(footprint.mean_reuse_distance(), Some(3.));
+}
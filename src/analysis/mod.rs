@@ -0,0 +1,5 @@
+mod footprint;
+mod warp;
+
+pub use footprint::{Footprint, Order};
+pub use warp::{AccessReport, WarpAccess, WarpConfig};
@@ -0,0 +1,218 @@
+use crate::{ArrayLayout, Endian};
+
+/// 线程束访存模型参数。
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct WarpConfig {
+    /// 每个线程束的线程数。
+    pub warp_size: usize,
+    /// 共享内存的 bank 数。
+    pub banks: usize,
+    /// 每个 bank 的宽度（字节）。
+    pub bank_width: usize,
+    /// 全局内存事务的段大小（字节）。
+    pub segment_size: usize,
+}
+
+impl Default for WarpConfig {
+    /// 默认参数：32 线程的线程束，32 个 4 字节宽的 bank，128 字节的全局内存段。
+    #[inline]
+    fn default() -> Self {
+        Self {
+            warp_size: 32,
+            banks: 32,
+            bank_width: 4,
+            segment_size: 128,
+        }
+    }
+}
+
+/// 一个线程束一次访存的分析结果。
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct WarpAccess {
+    /// 参与访存的活跃线程数。
+    pub active: usize,
+    /// 共享内存访问需要的波次数，无 bank 冲突时为 1。
+    pub wavefronts: usize,
+    /// 全局内存访问需要的事务数。
+    pub transactions: usize,
+}
+
+impl WarpAccess {
+    /// 共享内存 bank 冲突导致的额外波次数。
+    #[inline]
+    pub const fn bank_conflicts(&self) -> usize {
+        self.wavefronts.saturating_sub(1)
+    }
+}
+
+/// 访存分析报告，记录每个线程束每次访存的分析结果。
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct AccessReport {
+    /// 按线程束顺序排列的访存分析结果。
+    pub accesses: Vec<WarpAccess>,
+}
+
+impl AccessReport {
+    /// 所有访存的 bank 冲突总数。
+    pub fn bank_conflicts(&self) -> usize {
+        self.accesses.iter().map(WarpAccess::bank_conflicts).sum()
+    }
+
+    /// 所有访存的共享内存波次总数。
+    pub fn wavefronts(&self) -> usize {
+        self.accesses.iter().map(|a| a.wavefronts).sum()
+    }
+
+    /// 所有访存的全局内存事务总数。
+    pub fn transactions(&self) -> usize {
+        self.accesses.iter().map(|a| a.transactions).sum()
+    }
+
+    /// 访存代价，即共享内存波次与全局内存事务之和，可用于比较候选布局。
+    #[inline]
+    pub fn cost(&self) -> usize {
+        self.wavefronts() + self.transactions()
+    }
+}
+
+impl<const N: usize> ArrayLayout<N> {
+    /// 模拟线程束访问数组，分析共享内存 bank 冲突和全局内存事务。
+    ///
+    /// 布局的步长和偏移以字节为单位。`map` 将线程号映射到元素的大端序号，
+    /// 返回 `None` 的线程不参与访存。
+    /// 线程总数为元素数量按线程束大小向上取整，最后一个线程束中序号超出元素数量的线程不活跃。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, Endian::BigEndian, WarpConfig};
+    /// let config = WarpConfig::default();
+    /// let layout = ArrayLayout::<2>::new_contiguous(&[32, 32], BigEndian, 4);
+    ///
+    /// // 线程束读取一行：无冲突，合并为一个事务
+    /// let report = layout.warp_access(4, &config, Some);
+    /// assert_eq!(report.bank_conflicts(), 0);
+    /// assert_eq!(report.transactions(), 32);
+    ///
+    /// // 线程束读取一列：32 路冲突，每个线程一个事务
+    /// let report = layout.transpose(&[1, 0]).warp_access(4, &config, Some);
+    /// assert_eq!(report.bank_conflicts(), 32 * 31);
+    /// assert_eq!(report.transactions(), 32 * 32);
+    /// ```
+    pub fn warp_access(
+        &self,
+        element_size: usize,
+        config: &WarpConfig,
+        mut map: impl FnMut(usize) -> Option<usize>,
+    ) -> AccessReport {
+        let &WarpConfig {
+            warp_size,
+            banks,
+            bank_width,
+            segment_size,
+        } = config;
+        assert!(warp_size > 0 && banks > 0 && bank_width > 0 && segment_size > 0);
+
+        let len = self.num_elements();
+        let element_size = element_size.max(1) as isize;
+        let span = |addr: isize, unit: usize| {
+            let unit = unit as isize;
+            addr.div_euclid(unit)..=(addr + element_size - 1).div_euclid(unit)
+        };
+
+        let mut words = Vec::with_capacity(warp_size);
+        let mut segments = Vec::with_capacity(warp_size);
+        let accesses = (0..len.div_ceil(warp_size))
+            .map(|warp| {
+                words.clear();
+                segments.clear();
+
+                let mut active = 0;
+                for thread in warp * warp_size..((warp + 1) * warp_size).min(len) {
+                    let Some(index) = map(thread) else { continue };
+                    assert!(
+                        index < len,
+                        "thread {thread} maps to index {index} out of {len}"
+                    );
+                    active += 1;
+
+                    let addr = self.element_offset(index, Endian::BigEndian);
+                    for word in span(addr, bank_width) {
+                        words.push((word.rem_euclid(banks as _), word))
+                    }
+                    segments.extend(span(addr, segment_size))
+                }
+
+                // 同一 bank 上访问的不同字数决定波次数，访问同一个字的线程可以广播
+                words.sort_unstable();
+                words.dedup();
+                let mut wavefronts = 0;
+                for group in words.chunk_by(|a, b| a.0 == b.0) {
+                    wavefronts = wavefronts.max(group.len())
+                }
+
+                segments.sort_unstable();
+                segments.dedup();
+
+                WarpAccess {
+                    active,
+                    wavefronts,
+                    transactions: segments.len(),
+                }
+            })
+            .collect();
+
+        AccessReport { accesses }
+    }
+}
+
+#[test]
+fn test() {
+    let config = WarpConfig::default();
+    let tile = ArrayLayout::<2>::new_contiguous(&[32, 32], Endian::BigEndian, 4);
+    let padded = ArrayLayout::<2>::new(&[32, 32], &[33 * 4, 4], 0);
+
+    // 填充一列后按列访问没有 bank 冲突
+    let report = padded.transpose(&[1, 0]).warp_access(4, &config, Some);
+    assert_eq!(report.accesses.len(), 32);
+    assert!(report.accesses.iter().all(|a| a.active == 32));
+    assert_eq!(report.bank_conflicts(), 0);
+
+    // 8 字节元素跨两个 bank，一次访问需要两个波次
+    let wide = ArrayLayout::<2>::new_contiguous(&[32, 32], Endian::BigEndian, 8);
+    let report = wide.warp_access(8, &config, Some);
+    assert!(report.accesses.iter().all(|a| a.wavefronts == 2));
+    assert!(report.accesses.iter().all(|a| a.transactions == 2));
+
+    // 只有半个线程束活跃
+    let report = tile.warp_access(4, &config, |t| (t % 32 < 16).then_some(t));
+    assert!(report.accesses.iter().all(|a| a.active == 16));
+
+    // 元素数量不是线程束大小的整数倍，最后一个线程束不满
+    let small = ArrayLayout::<2>::new_contiguous(&[3, 5], Endian::BigEndian, 4);
+    let report = small.warp_access(4, &WarpConfig::default(), Some);
+    assert_eq!(report.accesses.len(), 1);
+    assert_eq!(report.accesses[0].active, 15);
+    let quarter = WarpConfig {
+        warp_size: 4,
+        ..WarpConfig::default()
+    };
+    let report = small.warp_access(4, &quarter, Some);
+    let active = report.accesses.iter().map(|a| a.active).collect::<Vec<_>>();
+    assert_eq!(active, [4, 4, 4, 3]);
+
+    // 按代价排序候选布局
+    let candidates = [
+        tile.transpose(&[1, 0]),
+        tile.clone(),
+        padded.transpose(&[1, 0]),
+    ];
+    let mut ranked = candidates
+        .iter()
+        .map(|l| l.warp_access(4, &config, Some).cost())
+        .enumerate()
+        .collect::<Vec<_>>();
+    ranked.sort_by_key(|&(_, cost)| cost);
+    assert_eq!(
+        ranked.iter().map(|&(i, _)| i).collect::<Vec<_>>(),
+        [1, 2, 0]
+    );
+}
@@ -0,0 +1,244 @@
+use crate::{ArrayLayout, Endian};
+
+/// 分块存储格式的描述。
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Blocking {
+    /// 分块前的逻辑形状，分块的轴向上取整到块大小的整数倍。
+    pub shape: Vec<usize>,
+    /// 分块的轴，升序排列。
+    pub block_axes: Vec<usize>,
+    /// 每个分块轴的块大小。
+    pub block_sizes: Vec<usize>,
+    /// 分块的顺序。
+    pub endian: Endian,
+    /// 元素的大小。
+    pub element_size: usize,
+}
+
+impl<const N: usize> ArrayLayout<N> {
+    /// 创建分块存储格式的布局，例如 NCHW16c 或矩阵的面板格式。
+    ///
+    /// 每个分块轴被划分为块数和块内两维，块内维度依次移动到存储的最内层。
+    /// 返回布局的形状与对原形状进行相应分块变换的结果一致：
+    /// 大端序下轴 `a` 变为 `[块数, 块大小]`，小端序下变为 `[块大小, 块数]`，
+    /// 因此逻辑下标仍在原形状的空间中。
+    ///
+    /// 轴长度不能被块大小整除时，块数向上取整，存储空间包含填充。
+    /// 此时块内下标与块号组合得到的逻辑下标可能超出原长度，超出的部分是填充，
+    /// 布局的元素数量是填充后的数量。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, Endian::BigEndian};
+    /// // NCHW16c，C = 20 向上取整为 2 块
+    /// let layout = ArrayLayout::<5>::new_blocked(&[2, 20, 3, 3], &[1], &[16], BigEndian, 4);
+    /// assert_eq!(layout.shape(), &[2, 2, 16, 3, 3]);
+    /// assert_eq!(layout.strides(), &[1152, 576, 4, 192, 64]);
+    ///
+    /// let blocking = layout.detect_blocking().unwrap();
+    /// assert_eq!(blocking.shape, &[2, 32, 3, 3]);
+    /// assert_eq!(blocking.block_axes, &[1]);
+    /// assert_eq!(blocking.block_sizes, &[16]);
+    /// ```
+    pub fn new_blocked(
+        shape: &[usize],
+        block_axes: &[usize],
+        block_sizes: &[usize],
+        endian: Endian,
+        element_size: usize,
+    ) -> Self {
+        assert_eq!(
+            block_axes.len(),
+            block_sizes.len(),
+            "block axes and block sizes must have the same length"
+        );
+        assert!(
+            block_axes.windows(2).all(|w| w[0] < w[1]),
+            "block axes must be in ascending order"
+        );
+        assert!(block_axes.iter().all(|&a| a < shape.len()));
+        assert!(block_sizes.iter().all(|&b| b > 0));
+
+        match endian {
+            Endian::BigEndian => Self::new_blocked_be(shape, block_axes, block_sizes, element_size),
+            Endian::LittleEndian => {
+                // 小端分块是反转维度顺序后的大端分块
+                let n = shape.len();
+                let shape = shape.iter().rev().copied().collect::<Vec<_>>();
+                let axes = block_axes
+                    .iter()
+                    .rev()
+                    .map(|&a| n - 1 - a)
+                    .collect::<Vec<_>>();
+                let sizes = block_sizes.iter().rev().copied().collect::<Vec<_>>();
+                Self::new_blocked_be(&shape, &axes, &sizes, element_size).reversed()
+            }
+        }
+    }
+
+    /// 识别布局是否是 [`new_blocked`](Self::new_blocked) 创建的分块存储格式。
+    ///
+    /// 偏移不为 0 或没有分块轴的布局返回 `None`。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, Endian::LittleEndian};
+    /// // 将 [K, N] 矩阵按 4x16 的面板存储
+    /// let layout = ArrayLayout::<4>::new_blocked(&[8, 32], &[0, 1], &[4, 16], LittleEndian, 2);
+    /// assert_eq!(layout.shape(), &[4, 2, 16, 2]);
+    ///
+    /// let blocking = layout.detect_blocking().unwrap();
+    /// assert_eq!(blocking.shape, &[8, 32]);
+    /// assert_eq!(blocking.block_sizes, &[4, 16]);
+    /// assert_eq!(blocking.endian, LittleEndian);
+    /// assert_eq!(blocking.element_size, 2);
+    /// ```
+    pub fn detect_blocking(&self) -> Option<Blocking> {
+        if self.offset() != 0 {
+            return None;
+        }
+        if let Some(ans) = self.detect_blocking_be() {
+            return Some(ans);
+        }
+        let Blocking {
+            shape,
+            block_axes,
+            block_sizes,
+            element_size,
+            ..
+        } = self.reversed().detect_blocking_be()?;
+        let m = shape.len();
+        Some(Blocking {
+            shape: shape.into_iter().rev().collect(),
+            block_axes: block_axes.into_iter().rev().map(|a| m - 1 - a).collect(),
+            block_sizes: block_sizes.into_iter().rev().collect(),
+            endian: Endian::LittleEndian,
+            element_size,
+        })
+    }
+
+    fn new_blocked_be(
+        shape: &[usize],
+        block_axes: &[usize],
+        block_sizes: &[usize],
+        element_size: usize,
+    ) -> Self {
+        // 逻辑形状中每一维的长度，以及在存储顺序中的位置
+        let mut dims = Vec::with_capacity(shape.len() + block_axes.len());
+        let mut inner = shape.len();
+        let mut blocks = block_axes.iter().zip(block_sizes).peekable();
+        for (axis, &d) in shape.iter().enumerate() {
+            match blocks.next_if(|&(&a, _)| a == axis) {
+                Some((_, &b)) => {
+                    dims.push((d.div_ceil(b), axis));
+                    dims.push((b, inner));
+                    inner += 1
+                }
+                None => dims.push((d, axis)),
+            }
+        }
+
+        // 外层维度按轴顺序排列，块内维度依次排在存储的最内层
+        let mut physical = (0..dims.len()).collect::<Vec<_>>();
+        physical.sort_unstable_by_key(|&i| dims[i].1);
+        let mut strides = vec![0; dims.len()];
+        let mut mul = element_size as isize;
+        for &i in physical.iter().rev() {
+            strides[i] = mul;
+            mul *= dims[i].0 as isize;
+        }
+
+        let shape = dims.iter().map(|&(d, _)| d).collect::<Vec<_>>();
+        Self::new(&shape, &strides, 0)
+    }
+
+    fn detect_blocking_be(&self) -> Option<Blocking> {
+        let shape = self.shape();
+        let strides = self.strides();
+        let n = self.ndim;
+
+        // 按步长从大到小得到存储顺序，步长相同时保持轴顺序
+        let mut order = (0..n).collect::<Vec<_>>();
+        order.sort_by_key(|&i| std::cmp::Reverse(strides[i].unsigned_abs()));
+        let element_size = strides[*order.last()?].unsigned_abs();
+
+        // 存储顺序的后缀是块内维度，每个块内维度在逻辑上紧跟在它的块数维度之后，
+        // 且至少一个块内维度在存储中被移动到了其他外层维度之后
+        let ascending = |axes: &[usize]| axes.windows(2).all(|w| w[0] < w[1]);
+        for split in (1..n).rev() {
+            let (outer, inner) = order.split_at(split);
+            if !ascending(outer)
+                || !ascending(inner)
+                || inner.iter().any(|&i| i == 0 || !outer.contains(&(i - 1)))
+                || inner.iter().all(|&i| outer.iter().all(|&j| j < i))
+            {
+                continue;
+            }
+
+            let mut logical = Vec::with_capacity(n - inner.len());
+            let mut block_axes = Vec::with_capacity(inner.len());
+            let mut block_sizes = Vec::with_capacity(inner.len());
+            for &axis in outer {
+                if inner.contains(&(axis + 1)) {
+                    block_axes.push(logical.len());
+                    block_sizes.push(shape[axis + 1]);
+                    logical.push(shape[axis] * shape[axis + 1])
+                } else {
+                    logical.push(shape[axis])
+                }
+            }
+            if Self::new_blocked_be(&logical, &block_axes, &block_sizes, element_size) == *self {
+                return Some(Blocking {
+                    shape: logical,
+                    block_axes,
+                    block_sizes,
+                    endian: Endian::BigEndian,
+                    element_size,
+                });
+            }
+        }
+        None
+    }
+
+    /// 反转维度顺序。
+    fn reversed(&self) -> Self {
+        let perm = (0..self.ndim).rev().collect::<Vec<_>>();
+        self.transpose(&perm)
+    }
+}
+
+#[test]
+fn test() {
+    // 分块布局与对连续布局分块后转置的结果一致
+    let contiguous = ArrayLayout::<6>::new_contiguous(&[2, 3, 4, 5, 16], Endian::BigEndian, 4);
+    let blocked = ArrayLayout::<6>::new_blocked(&[2, 48, 4, 5], &[1], &[16], Endian::BigEndian, 4);
+    assert!(blocked == contiguous.transpose(&[0, 1, 4, 2, 3]));
+
+    // 小端序
+    let blocked = ArrayLayout::<6>::new_blocked(&[6, 4], &[1], &[2], Endian::LittleEndian, 1);
+    assert_eq!(blocked.shape(), &[6, 2, 2]);
+    assert_eq!(blocked.strides(), &[2, 1, 12]);
+    let blocking = blocked.detect_blocking().unwrap();
+    assert_eq!(blocking.shape, &[6, 4]);
+    assert_eq!(blocking.block_axes, &[1]);
+    assert_eq!(blocking.endian, Endian::LittleEndian);
+
+    // 对存储最内层的轴分块不改变存储顺序，不视为分块格式
+    let trivial = ArrayLayout::<6>::new_blocked(&[6, 4], &[0], &[3], Endian::LittleEndian, 1);
+    assert!(
+        trivial
+            == ArrayLayout::new_contiguous(&[6, 4], Endian::LittleEndian, 1).tile_le(0, &[3, 2])
+    );
+    assert!(trivial.detect_blocking().is_none());
+
+    // 所有轴都分块
+    let blocked =
+        ArrayLayout::<6>::new_blocked(&[8, 12, 6], &[0, 1, 2], &[2, 3, 2], Endian::BigEndian, 8);
+    let blocking = blocked.detect_blocking().unwrap();
+    assert_eq!(blocking.shape, &[8, 12, 6]);
+    assert_eq!(blocking.block_axes, &[0, 1, 2]);
+    assert_eq!(blocking.block_sizes, &[2, 3, 2]);
+
+    // 普通布局不是分块格式
+    assert!(contiguous.detect_blocking().is_none());
+    assert!(contiguous.transpose(&[1, 0]).detect_blocking().is_none());
+    assert!(contiguous.slice(0, 1, 1, 1).detect_blocking().is_none());
+}
@@ -0,0 +1,467 @@
+use crate::{ArrayLayout, Transform};
+use std::iter::zip;
+
+/// 延迟执行的布局变换流水线。
+///
+/// 变换先被记录下来，[`LayoutBuilder::finish`] 时融合相邻的切片、抵消成对的转置、
+/// 消除分块后立即合并的往返，然后一次性应用，结果与逐个应用变换相同。
+///
+/// ```rust
+/// # use ndarray_layout::{ArrayLayout, Endian::BigEndian};
+/// let layout = ArrayLayout::<4>::new_contiguous(&[4, 6], BigEndian, 4);
+/// let builder = layout
+///     .builder()
+///     .tile_be(1, &[2, 3])
+///     .merge_be(1, 2)
+///     .transpose(&[1, 0])
+///     .slice(0, 1, 1, 5)
+///     .slice(0, 4, -2, 2)
+///     .transpose(&[1, 0]);
+/// assert_eq!(builder.fuse().len(), 3);
+///
+/// let eager = layout.slice(1, 1, 1, 5).slice(1, 4, -2, 2);
+/// assert!(builder.finish().unwrap() == eager);
+/// ```
+#[derive(Clone)]
+pub struct LayoutBuilder<const N: usize> {
+    base: ArrayLayout<N>,
+    ops: Vec<Transform>,
+}
+
+impl<const N: usize> ArrayLayout<N> {
+    /// 从这个布局开始构造延迟执行的变换流水线。
+    #[inline]
+    pub fn builder(&self) -> LayoutBuilder<N> {
+        LayoutBuilder::new(self.clone())
+    }
+}
+
+impl<const N: usize> LayoutBuilder<N> {
+    /// 从基础布局开始构造流水线。
+    #[inline]
+    pub fn new(base: ArrayLayout<N>) -> Self {
+        Self { base, ops: vec![] }
+    }
+
+    /// 已记录的变换。
+    #[inline]
+    pub fn ops(&self) -> &[Transform] {
+        &self.ops
+    }
+
+    /// 记录一个变换。
+    #[inline]
+    pub fn then(mut self, op: Transform) -> Self {
+        self.ops.push(op);
+        self
+    }
+
+    /// 见 [`ArrayLayout::index`]。
+    #[inline]
+    pub fn index(self, axis: usize, index: usize) -> Self {
+        self.then(Transform::Index { axis, index })
+    }
+
+    /// 见 [`ArrayLayout::slice`]。
+    #[inline]
+    pub fn slice(self, axis: usize, start: usize, step: isize, len: usize) -> Self {
+        self.then(Transform::Slice {
+            axis,
+            start,
+            step,
+            len,
+        })
+    }
+
+    /// 见 [`ArrayLayout::tile_be`]。
+    #[inline]
+    pub fn tile_be(self, axis: usize, tiles: &[usize]) -> Self {
+        let tiles = tiles.to_vec();
+        self.then(Transform::TileBe { axis, tiles })
+    }
+
+    /// 见 [`ArrayLayout::tile_le`]。
+    #[inline]
+    pub fn tile_le(self, axis: usize, tiles: &[usize]) -> Self {
+        let tiles = tiles.to_vec();
+        self.then(Transform::TileLe { axis, tiles })
+    }
+
+    /// 见 [`ArrayLayout::merge_be`]。
+    #[inline]
+    pub fn merge_be(self, start: usize, len: usize) -> Self {
+        self.then(Transform::MergeBe { start, len })
+    }
+
+    /// 见 [`ArrayLayout::merge_le`]。
+    #[inline]
+    pub fn merge_le(self, start: usize, len: usize) -> Self {
+        self.then(Transform::MergeLe { start, len })
+    }
+
+    /// 见 [`ArrayLayout::merge_free`]。
+    #[inline]
+    pub fn merge_free(self, start: usize, len: usize) -> Self {
+        self.then(Transform::MergeFree { start, len })
+    }
+
+    /// 见 [`ArrayLayout::transpose`]。
+    #[inline]
+    pub fn transpose(self, perm: &[usize]) -> Self {
+        let perm = perm.to_vec();
+        self.then(Transform::Transpose { perm })
+    }
+
+    /// 见 [`ArrayLayout::broadcast`]。
+    #[inline]
+    pub fn broadcast(self, axis: usize, times: usize) -> Self {
+        self.then(Transform::Broadcast { axis, times })
+    }
+
+    /// 融合记录的变换，返回实际要应用的变换。
+    pub fn fuse(&self) -> Vec<Transform> {
+        let mut fused = Fused {
+            ops: Vec::with_capacity(self.ops.len()),
+            shapes: vec![self.base.shape().to_vec()],
+            valid: true,
+        };
+        for op in &self.ops {
+            fused.push(op.clone())
+        }
+        fused.ops
+    }
+
+    /// 融合所有变换并在基础布局上原地应用，有合并失败时返回 `None`。
+    pub fn finish(self) -> Option<ArrayLayout<N>> {
+        let ops = self.fuse();
+        let mut ans = self.base;
+        ops.iter()
+            .all(|op| op.apply_inplace(&mut ans))
+            .then_some(ans)
+    }
+}
+
+/// 融合中的变换，`shapes[i]` 是第 i 个变换的输入形状。
+///
+/// 遇到与形状不匹配的变换后不再融合，应用时在这个变换处失败。
+struct Fused {
+    ops: Vec<Transform>,
+    shapes: Vec<Vec<usize>>,
+    valid: bool,
+}
+
+impl Fused {
+    fn push(&mut self, op: Transform) {
+        if !self.valid {
+            self.ops.push(op);
+            return;
+        }
+        let shape = self.shapes.last().unwrap();
+        match self.fuse(&op) {
+            Some(Some(fused)) => {
+                self.pop();
+                self.push(fused)
+            }
+            Some(None) => self.pop(),
+            None => {
+                if let Transform::Slice {
+                    axis,
+                    start: 0,
+                    step: 1,
+                    len,
+                } = op
+                {
+                    // 完整的切片不改变布局
+                    if shape.get(axis).is_some_and(|&d| d > 0 && len >= d) {
+                        return;
+                    }
+                }
+                match shape_after(shape, &op) {
+                    Some(next) => self.shapes.push(next),
+                    None => self.valid = false,
+                }
+                self.ops.push(op)
+            }
+        }
+    }
+
+    fn pop(&mut self) {
+        self.ops.pop();
+        self.shapes.pop();
+    }
+
+    /// 尝试与上一个变换融合。`Some(Some(op))` 表示两者融合为 `op`，`Some(None)` 表示两者抵消。
+    fn fuse(&self, op: &Transform) -> Option<Option<Transform>> {
+        let last = self.ops.last()?;
+        let shape = &self.shapes[self.shapes.len() - 2];
+        match (last, op) {
+            (
+                &Transform::Slice {
+                    axis,
+                    start,
+                    step,
+                    len,
+                },
+                &Transform::Slice {
+                    axis: axis_,
+                    start: start_,
+                    step: step_,
+                    len: len_,
+                },
+            ) if axis == axis_ => {
+                let (start, len) = effective(shape[axis], start, step, len)?;
+                let (start_, len_) = effective(len, start_, step_, len_)?;
+                Some(Some(Transform::Slice {
+                    axis,
+                    start: (start as isize + start_ as isize * step) as _,
+                    step: step * step_,
+                    len: len_,
+                }))
+            }
+            (Transform::Transpose { perm }, Transform::Transpose { perm: perm_ }) => {
+                let n = shape.len();
+                let (first, second) = (full_perm(perm, n)?, full_perm(perm_, n)?);
+                let perm = second.iter().map(|&i| first[i]).collect::<Vec<_>>();
+                if perm.iter().enumerate().all(|(i, &j)| i == j) {
+                    Some(None)
+                } else {
+                    Some(Some(Transform::Transpose { perm }))
+                }
+            }
+            (Transform::TileBe { axis, tiles }, &Transform::MergeBe { start, len })
+            | (Transform::TileLe { axis, tiles }, &Transform::MergeLe { start, len })
+                if *axis == start && tiles.len() == len =>
+            {
+                // 分块与维度长度不符时保留，应用时失败；长度为 1 的维度合并后步长变为 0，与原来不同
+                let d = shape[start];
+                (d > 1 && product(tiles) == Some(d)).then_some(None)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// 计算切片在长度为 `d` 的维度上实际的起点和长度，与 [`ArrayLayout::slice`] 相同，参数无效时返回 `None`。
+fn effective(d: usize, start: usize, step: isize, len: usize) -> Option<(usize, usize)> {
+    use std::cmp::Ordering::*;
+    match step.cmp(&0) {
+        Greater if start < d => Some((start, (d - start).div_ceil(step as _).min(len))),
+        Equal if start < d => Some((start, len)),
+        Less if d > 0 => {
+            let start = start.min(d - 1);
+            Some((start, (start + 1).div_ceil((-step) as _).min(len)))
+        }
+        _ => None,
+    }
+}
+
+/// 把 [`ArrayLayout::transpose`] 的参数展开为 `n` 维的完整排列。
+fn full_perm(perm: &[usize], n: usize) -> Option<Vec<usize>> {
+    let mut sorted = perm.to_vec();
+    sorted.sort_unstable();
+    if sorted.windows(2).any(|w| w[0] == w[1]) || sorted.last().is_some_and(|&i| i >= n) {
+        return None;
+    }
+    let mut ans = (0..n).collect::<Vec<_>>();
+    for (&i, &j) in zip(&sorted, perm) {
+        ans[i] = j
+    }
+    Some(ans)
+}
+
+/// 计算变换后的形状，变换与形状不匹配时返回 `None`。
+fn shape_after(shape: &[usize], op: &Transform) -> Option<Vec<usize>> {
+    let ndim = shape.len();
+    let mut ans = shape.to_vec();
+    match *op {
+        Transform::Index { axis, index } => {
+            (index < *shape.get(axis)?).then_some(())?;
+            ans.remove(axis);
+        }
+        Transform::Slice {
+            axis,
+            start,
+            step,
+            len,
+        } => ans[axis] = effective(*shape.get(axis)?, start, step, len)?.1,
+        Transform::TileBe { axis, ref tiles } | Transform::TileLe { axis, ref tiles } => {
+            (product(tiles)? == *shape.get(axis)?).then_some(())?;
+            ans.splice(axis..axis + 1, tiles.iter().copied());
+        }
+        Transform::MergeBe { start, len }
+        | Transform::MergeLe { start, len }
+        | Transform::MergeFree { start, len } => {
+            let range = start..start.checked_add(len).filter(|&end| end <= ndim)?;
+            if len > 0 {
+                let d = product(&shape[range.clone()])?;
+                ans.splice(range, [d]);
+            }
+        }
+        Transform::Transpose { ref perm } => {
+            ans = full_perm(perm, ndim)?.iter().map(|&i| shape[i]).collect()
+        }
+        Transform::Broadcast { axis, times } => *ans.get_mut(axis)? = times,
+        Transform::Unindex {
+            axis, index, len, ..
+        } => {
+            (axis <= ndim && index < len).then_some(())?;
+            ans.insert(axis, len)
+        }
+        Transform::Unslice {
+            axis, step, len, ..
+        } => {
+            (step != 0).then_some(())?;
+            *ans.get_mut(axis)? = len
+        }
+        Transform::Windows { axis, size, step } => {
+            let d = *shape.get(axis)?;
+            (0 < size && size <= d && step > 0).then_some(())?;
+            ans[axis] = (d - size) / step + 1;
+            ans.push(size)
+        }
+        Transform::Unwindows { axis, len } => {
+            (axis + 1 < ndim).then_some(())?;
+            ans.pop();
+            ans[axis] = len
+        }
+    }
+    Some(ans)
+}
+
+/// 不溢出地求乘积。
+fn product(dims: &[usize]) -> Option<usize> {
+    dims.iter().copied().try_fold(1, usize::checked_mul)
+}
+
+#[test]
+fn test() {
+    use crate::Endian::BigEndian;
+
+    // 随机生成有效的变换链，比较融合后与逐个应用的结果
+    let mut seed = 0x9e37_79b9_7f4a_7c15_u64;
+    let mut rand = |n: usize| {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed as usize % n
+    };
+    let mut fused = 0;
+    for _ in 0..2000 {
+        let shape = (0..1 + rand(3)).map(|_| 1 + rand(6)).collect::<Vec<_>>();
+        let base = ArrayLayout::<4>::new_contiguous(&shape, BigEndian, 4);
+        let mut eager = base.clone();
+        let mut builder = base.builder();
+        for _ in 0..8 {
+            let n = eager.ndim();
+            let axis = rand(n);
+            let d = eager.shape()[axis];
+            let op = match rand(7) {
+                0 if n > 1 => Transform::Index {
+                    axis,
+                    index: rand(d),
+                },
+                1 | 2 => Transform::Slice {
+                    axis,
+                    start: rand(d),
+                    step: [1, 2, -1, -3, 0][rand(5)],
+                    len: 1 + rand(d + 1),
+                },
+                3 => {
+                    let f = (1..=d).filter(|f| d.is_multiple_of(*f)).collect::<Vec<_>>();
+                    let f = f[rand(f.len())];
+                    let tiles = vec![d / f, f];
+                    match rand(2) {
+                        0 => Transform::TileBe { axis, tiles },
+                        _ => Transform::TileLe { axis, tiles },
+                    }
+                }
+                4 if axis + 1 < n => match rand(3) {
+                    0 => Transform::MergeBe {
+                        start: axis,
+                        len: 2,
+                    },
+                    1 => Transform::MergeLe {
+                        start: axis,
+                        len: 2,
+                    },
+                    _ => Transform::MergeFree {
+                        start: axis,
+                        len: 2,
+                    },
+                },
+                5 => {
+                    let mut perm = (0..n).collect::<Vec<_>>();
+                    for i in (1..n).rev() {
+                        perm.swap(i, rand(i + 1))
+                    }
+                    perm.truncate(1 + rand(n));
+                    Transform::Transpose { perm }
+                }
+                6 if d == 1 => Transform::Broadcast {
+                    axis,
+                    times: 1 + rand(3),
+                },
+                _ => continue,
+            };
+            // 用不原地修改的变换作为参照，合并失败的变换不记录，保证链上所有变换有效
+            let layout = match op {
+                Transform::Index { axis, index } => Some(eager.index(axis, index)),
+                Transform::Slice {
+                    axis,
+                    start,
+                    step,
+                    len,
+                } => Some(eager.slice(axis, start, step, len)),
+                Transform::TileBe { axis, ref tiles } => Some(eager.tile_be(axis, tiles)),
+                Transform::TileLe { axis, ref tiles } => Some(eager.tile_le(axis, tiles)),
+                Transform::MergeBe { start, len } => eager.merge_be(start, len),
+                Transform::MergeLe { start, len } => eager.merge_le(start, len),
+                Transform::MergeFree { start, len } => eager.merge_free(start, len),
+                Transform::Transpose { ref perm } => Some(eager.transpose(perm)),
+                Transform::Broadcast { axis, times } => Some(eager.broadcast(axis, times)),
+                _ => unreachable!(),
+            };
+            if let Some(layout) = layout {
+                eager = layout;
+                builder = builder.then(op)
+            }
+        }
+        fused += builder.ops().len() - builder.fuse().len();
+        assert!(builder.finish().unwrap() == eager);
+    }
+    assert!(fused > 0);
+
+    // 转置抵消、分块往返
+    let base = ArrayLayout::<4>::new_contiguous(&[2, 3, 4], BigEndian, 4);
+    let builder = base
+        .builder()
+        .transpose(&[2, 0])
+        .tile_le(1, &[3, 1])
+        .merge_le(1, 2)
+        .transpose(&[2, 0]);
+    assert!(builder.fuse().is_empty());
+    assert!(builder.finish().unwrap() == base);
+    // 无效的变换不会被融合掉，与逐个应用一样失败
+    let base = ArrayLayout::<4>::new_contiguous(&[3, 4], BigEndian, 4);
+    let builder = base.builder().tile_be(0, &[2, 2]).merge_be(0, 2);
+    assert_eq!(builder.fuse().len(), 2);
+    assert!(Transform::replay(builder.ops(), &base).is_none());
+    assert!(builder.finish().is_none());
+    let builder = base
+        .builder()
+        .index(2, 0)
+        .transpose(&[1, 0])
+        .transpose(&[1, 0]);
+    assert_eq!(builder.fuse().len(), 3);
+    assert!(builder.finish().is_none());
+    let builder = base.builder().slice(0, 1, 1, 2).slice(0, 5, 1, 1);
+    assert!(builder.finish().is_none());
+
+    // 全为 1 的分块合并后步长不同，不能抵消
+    let one = ArrayLayout::<4>::new(&[1], &[4], 0);
+    let builder = one.builder().tile_be(0, &[1, 1]).merge_be(0, 2);
+    assert_eq!(builder.fuse().len(), 2);
+    let eager = one.tile_be(0, &[1, 1]).merge_be(0, 2).unwrap();
+    assert_eq!(eager.strides(), &[0]);
+    assert!(builder.finish().unwrap() == eager);
+}
@@ -0,0 +1,251 @@
+use crate::ArrayLayout;
+use std::iter::zip;
+
+/// 生成代码的目标语言。
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Lang {
+    /// C，下标转换为 `int64_t` 计算。
+    C,
+    /// OpenCL C，下标转换为 `long` 计算。
+    OpenCl,
+}
+
+impl Lang {
+    fn cast(self, var: &str) -> String {
+        match self {
+            Self::C => format!("(int64_t){var}"),
+            Self::OpenCl => format!("(long){var}"),
+        }
+    }
+}
+
+impl<const N: usize> ArrayLayout<N> {
+    /// 生成计算元素偏移的表达式，单位与步长相同。
+    ///
+    /// `index_vars` 的长度等于维数时，每个变量是对应轴的下标；
+    /// 只有一个变量时，它是按大端顺序遍历数组的线性下标，用除法和取余的链还原各轴下标。
+    /// 常量步长直接折叠，长度为 1 的维度被省略，线性下标模式下能合并的维度被合并以减少除法。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, Endian::BigEndian, Lang};
+    /// let layout = ArrayLayout::<4>::new_contiguous(&[2, 3, 4], BigEndian, 4);
+    /// assert_eq!(
+    ///     layout.emit_offset_expr(Lang::C, &["i", "j", "k"]),
+    ///     "(int64_t)i * 48 + (int64_t)j * 16 + (int64_t)k * 4",
+    /// );
+    /// // 连续的维度合并后不需要除法
+    /// assert_eq!(layout.emit_offset_expr(Lang::C, &["idx"]), "(int64_t)idx * 4");
+    ///
+    /// let layout = layout.transpose(&[1, 0]).slice(2, 3, -1, 4).index(0, 1);
+    /// assert_eq!(
+    ///     layout.emit_offset_expr(Lang::OpenCl, &["idx"]),
+    ///     "28 + (long)idx / 4 * 48 - (long)idx % 4 * 4",
+    /// );
+    /// ```
+    pub fn emit_offset_expr(&self, lang: Lang, index_vars: &[&str]) -> String {
+        let shape = self.shape();
+        let strides = self.strides();
+
+        let mut terms = Vec::new();
+        if index_vars.len() == self.ndim() {
+            for ((&d, &s), var) in zip(zip(shape, strides), index_vars) {
+                if d != 1 && s != 0 {
+                    terms.push((lang.cast(var), s))
+                }
+            }
+        } else {
+            assert_eq!(
+                index_vars.len(),
+                1,
+                "index_vars must have one var per axis or a single linear index"
+            );
+            // 大端合并，按从内到外的顺序保存
+            let mut dims = Vec::<(usize, isize)>::new();
+            for (&d, &s) in zip(shape, strides).rev().filter(|(d, _)| **d != 1) {
+                match dims.last_mut() {
+                    Some((d_, s_)) if s == *s_ * *d_ as isize => *d_ *= d,
+                    _ => dims.push((d, s)),
+                }
+            }
+
+            let var = lang.cast(index_vars[0]);
+            let mut div = 1;
+            for (i, &(d, s)) in dims.iter().enumerate() {
+                if s != 0 {
+                    let mut expr = var.clone();
+                    if div != 1 {
+                        expr = format!("{expr} / {div}")
+                    }
+                    if i + 1 != dims.len() {
+                        expr = format!("{expr} % {d}")
+                    }
+                    terms.push((expr, s))
+                }
+                div *= d
+            }
+            terms.reverse()
+        }
+
+        let offset = self.offset();
+        let mut ans = String::new();
+        if offset != 0 {
+            ans = offset.to_string()
+        }
+        for (expr, s) in terms {
+            let mul = |s: isize| {
+                if s == 1 {
+                    expr.clone()
+                } else {
+                    format!("{expr} * {s}")
+                }
+            };
+            if ans.is_empty() {
+                ans = mul(s)
+            } else if s < 0 {
+                ans = format!("{ans} - {}", mul(-s))
+            } else {
+                ans = format!("{ans} + {}", mul(s))
+            }
+        }
+        if ans.is_empty() {
+            ans = "0".into()
+        }
+        ans
+    }
+}
+
+#[test]
+fn test() {
+    use crate::Endian::BigEndian;
+
+    /// 只支持整数、变量、类型转换和 `+ - * / %` 的 C 表达式求值。
+    fn eval(expr: &str, vars: &[(&str, i64)]) -> i64 {
+        fn tokens(expr: &str) -> Vec<String> {
+            let mut ans = Vec::new();
+            let mut chars = expr.chars().peekable();
+            while let Some(c) = chars.next() {
+                match c {
+                    ' ' => {}
+                    '(' if chars.peek().is_some_and(|c| c.is_alphabetic()) => {
+                        // 类型转换不影响 i64 上的计算
+                        for c in chars.by_ref() {
+                            if c == ')' {
+                                break;
+                            }
+                        }
+                    }
+                    c if c.is_alphanumeric() || c == '_' => {
+                        let mut s = c.to_string();
+                        while let Some(&c) = chars.peek().filter(|c| c.is_alphanumeric()) {
+                            s.push(c);
+                            chars.next();
+                        }
+                        ans.push(s)
+                    }
+                    c => ans.push(c.to_string()),
+                }
+            }
+            ans
+        }
+
+        struct Parser<'a> {
+            tokens: Vec<String>,
+            pos: usize,
+            vars: &'a [(&'a str, i64)],
+        }
+        impl Parser<'_> {
+            fn peek(&self) -> Option<&str> {
+                self.tokens.get(self.pos).map(String::as_str)
+            }
+            fn next(&mut self) -> String {
+                self.pos += 1;
+                self.tokens[self.pos - 1].clone()
+            }
+            fn sum(&mut self) -> i64 {
+                let mut ans = self.product();
+                while let Some(op @ ("+" | "-")) = self.peek() {
+                    let add = op == "+";
+                    self.next();
+                    let rhs = self.product();
+                    ans = if add { ans + rhs } else { ans - rhs }
+                }
+                ans
+            }
+            fn product(&mut self) -> i64 {
+                let mut ans = self.unary();
+                while let Some(op @ ("*" | "/" | "%")) = self.peek() {
+                    let op = op.to_string();
+                    self.next();
+                    let rhs = self.unary();
+                    ans = match &*op {
+                        "*" => ans * rhs,
+                        "/" => ans / rhs,
+                        _ => ans % rhs,
+                    }
+                }
+                ans
+            }
+            fn unary(&mut self) -> i64 {
+                let token = self.next();
+                match &*token {
+                    "-" => -self.unary(),
+                    "(" => {
+                        let ans = self.sum();
+                        assert_eq!(self.next(), ")");
+                        ans
+                    }
+                    t => t
+                        .parse()
+                        .unwrap_or_else(|_| self.vars.iter().find(|(n, _)| *n == t).unwrap().1),
+                }
+            }
+        }
+
+        let mut parser = Parser {
+            tokens: tokens(expr),
+            pos: 0,
+            vars,
+        };
+        let ans = parser.sum();
+        assert_eq!(parser.pos, parser.tokens.len(), "{expr}");
+        ans
+    }
+
+    let layouts = [
+        ArrayLayout::<4>::new_contiguous(&[2, 3, 4], BigEndian, 4),
+        ArrayLayout::<4>::new_contiguous(&[2, 1, 3, 4], BigEndian, 2)
+            .transpose(&[3, 1, 2, 0])
+            .slice(3, 2, -1, 3),
+        ArrayLayout::<4>::new(&[3, 1, 5], &[-20, 7, 4], 100).broadcast(1, 4),
+        ArrayLayout::<4>::new(&[4, 2, 3], &[6, 0, 2], 0),
+        ArrayLayout::<4>::new(&[1, 1], &[5, 6], 3),
+    ];
+    for layout in layouts {
+        let shape = layout.shape();
+        let names = ["a", "b", "c", "d"];
+        let per_axis = layout.emit_offset_expr(Lang::C, &names[..shape.len()]);
+        let linear = layout.emit_offset_expr(Lang::OpenCl, &["idx"]);
+        for idx in 0..layout.num_elements() {
+            let expected = layout.element_offset(idx, BigEndian) as i64;
+            assert_eq!(eval(&linear, &[("idx", idx as _)]), expected, "{linear}");
+
+            let mut rem = idx;
+            let mut vars = vec![("", 0); shape.len()];
+            for (i, &d) in shape.iter().enumerate().rev() {
+                vars[i] = (names[i], (rem % d) as i64);
+                rem /= d
+            }
+            assert_eq!(eval(&per_axis, &vars), expected, "{per_axis}");
+        }
+    }
+
+    let layout = ArrayLayout::<4>::new(&[3, 1, 5], &[-20, 7, 4], 0);
+    assert_eq!(
+        layout.emit_offset_expr(Lang::C, &["i", "j", "k"]),
+        "(int64_t)i * -20 + (int64_t)k * 4"
+    );
+    assert_eq!(
+        ArrayLayout::<4>::new(&[1], &[5], 0).emit_offset_expr(Lang::C, &["i"]),
+        "0"
+    );
+}
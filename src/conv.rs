@@ -0,0 +1,164 @@
+use crate::{ArrayLayout, SliceArg, WindowArg};
+
+/// 卷积输入在内存中的数据格式。
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum DataFormat {
+    /// 通道维度在空间维度外层。
+    Nchw,
+    /// 通道维度在最内层。
+    Nhwc,
+}
+
+/// im2col 变换的结果。
+#[derive(Clone, PartialEq, Eq)]
+pub struct Im2col<const N: usize> {
+    /// 从步长识别出的输入数据格式。
+    pub format: DataFormat,
+    /// 卷积窗口构成的 6 维布局，形状为 `(N, C, KH, KW, OH, OW)`。
+    ///
+    /// 不存在零拷贝视图时，按此布局收集数据并合并中间 3 维和最后 2 维即得到 im2col 矩阵。
+    pub windows: ArrayLayout<N>,
+    /// im2col 矩阵 `(N, C·KH·KW, OH·OW)` 的零拷贝视图，不存在时为 `None`。
+    ///
+    /// 两种数据格式的行都按 `C·KH·KW` 展开。NHWC 的通道在最内层，
+    /// 通道数大于 1 且卷积核大于 1x1 时通常不能按这个顺序合并，需要收集数据。
+    pub view: Option<ArrayLayout<N>>,
+}
+
+impl<const N: usize> ArrayLayout<N> {
+    /// 计算二维卷积的 im2col 布局。
+    ///
+    /// 输入是逻辑形状为 `(N, C, H, W)` 的 4 维布局，数据格式由步长识别：
+    /// 通道步长小于空间步长时为 NHWC，否则为 NCHW。
+    /// `kernel`、`stride` 和 `dilation` 依次对应 `(H, W)` 两个空间维度，不考虑填充。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, DataFormat, Endian::BigEndian};
+    /// let input = ArrayLayout::<6>::new_contiguous(&[2, 3, 8, 8], BigEndian, 4);
+    ///
+    /// // 1x1 卷积的 im2col 矩阵就是输入本身
+    /// let im2col = input.conv_im2col_layout([1, 1], [1, 1], [1, 1]);
+    /// assert_eq!(im2col.format, DataFormat::Nchw);
+    /// let view = im2col.view.unwrap();
+    /// assert_eq!(view.shape(), &[2, 3, 64]);
+    /// assert_eq!(view.strides(), &[768, 256, 4]);
+    ///
+    /// // 3x3 卷积需要收集数据
+    /// let im2col = input.conv_im2col_layout([3, 3], [1, 1], [1, 1]);
+    /// assert!(im2col.view.is_none());
+    /// assert_eq!(im2col.windows.shape(), &[2, 3, 3, 3, 6, 6]);
+    /// assert_eq!(im2col.windows.strides(), &[768, 256, 32, 4, 32, 4]);
+    /// ```
+    pub fn conv_im2col_layout(
+        &self,
+        kernel: [usize; 2],
+        stride: [usize; 2],
+        dilation: [usize; 2],
+    ) -> Im2col<N> {
+        let &[_, c, h, w] = self.shape() else {
+            panic!("im2col requires a 4-D (N, C, H, W) layout")
+        };
+        let &[_, sc, sh, sw] = self.strides() else {
+            unreachable!()
+        };
+        let [kh, kw] = kernel;
+        let [dh, dw] = dilation;
+        assert!(kh > 0 && kw > 0 && dh > 0 && dw > 0);
+
+        let spatial = [(h, sh), (w, sw)]
+            .into_iter()
+            .filter(|&(d, _)| d > 1)
+            .map(|(_, s)| s.unsigned_abs())
+            .min();
+        let format = match spatial {
+            Some(s) if c > 1 && sc.unsigned_abs() < s => DataFormat::Nhwc,
+            _ => DataFormat::Nchw,
+        };
+
+        // (N, C, OH, OW, KH, KW)
+        let windows = self
+            .windows_many(&[
+                WindowArg {
+                    axis: 2,
+                    size: (kh - 1) * dh + 1,
+                    step: stride[0],
+                },
+                WindowArg {
+                    axis: 3,
+                    size: (kw - 1) * dw + 1,
+                    step: stride[1],
+                },
+            ])
+            .slice_many(&[
+                SliceArg {
+                    axis: 4,
+                    start: 0,
+                    step: dh as _,
+                    len: kh,
+                },
+                SliceArg {
+                    axis: 5,
+                    start: 0,
+                    step: dw as _,
+                    len: kw,
+                },
+            ]);
+        let windows = windows.transpose(&[0, 1, 4, 5, 2, 3]);
+
+        let view = windows.merge_be(4, 2).and_then(|l| l.merge_be(1, 3));
+        Im2col {
+            format,
+            windows,
+            view,
+        }
+    }
+}
+
+#[test]
+fn test() {
+    use crate::Endian::BigEndian;
+
+    // NHWC 存储，逻辑形状为 (N, C, H, W)
+    let nhwc =
+        ArrayLayout::<6>::new_contiguous(&[2, 5, 5, 3], BigEndian, 1).transpose(&[0, 3, 1, 2]);
+    let im2col = nhwc.conv_im2col_layout([1, 1], [1, 1], [1, 1]);
+    assert_eq!(im2col.format, DataFormat::Nhwc);
+    let view = im2col.view.unwrap();
+    assert_eq!(view.shape(), &[2, 3, 25]);
+    assert_eq!(view.strides(), &[75, 1, 3]);
+
+    // 卷积核覆盖整个输入
+    let nchw = ArrayLayout::<6>::new_contiguous(&[2, 3, 4, 5], BigEndian, 1);
+    let view = nchw
+        .conv_im2col_layout([4, 5], [1, 1], [1, 1])
+        .view
+        .unwrap();
+    assert_eq!(view.shape(), &[2, 60, 1]);
+    assert_eq!(view.strides(), &[60, 1, 0]);
+
+    // NHWC 格式下卷积核覆盖整行时，`KH·KW·C` 连续但 `C·KH·KW` 不连续，需要收集
+    let im2col = nhwc.conv_im2col_layout([2, 5], [1, 1], [1, 1]);
+    assert!(im2col.view.is_none());
+    assert_eq!(im2col.windows.shape(), &[2, 3, 2, 5, 4, 1]);
+    assert_eq!(im2col.windows.strides(), &[75, 1, 15, 3, 15, 3]);
+    // 单通道的 NHWC 可以按 `C·KH·KW` 合并
+    let single =
+        ArrayLayout::<6>::new_contiguous(&[2, 5, 5, 1], BigEndian, 1).transpose(&[0, 3, 1, 2]);
+    let view = single
+        .conv_im2col_layout([2, 5], [1, 1], [1, 1])
+        .view
+        .unwrap();
+    assert_eq!(view.shape(), &[2, 10, 4]);
+    assert_eq!(view.strides(), &[25, 1, 5]);
+    assert!(
+        nchw.conv_im2col_layout([2, 5], [1, 1], [1, 1])
+            .view
+            .is_none()
+    );
+
+    // 空洞卷积
+    let im2col = nchw.conv_im2col_layout([2, 2], [1, 2], [2, 3]);
+    assert!(im2col.view.is_none());
+    assert_eq!(im2col.windows.shape(), &[2, 3, 2, 2, 2, 1]);
+    assert_eq!(im2col.windows.strides(), &[60, 20, 10, 3, 5, 2]);
+}
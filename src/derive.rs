@@ -0,0 +1,437 @@
+use crate::{ArrayLayout, Transform};
+use std::iter::zip;
+
+impl<const N: usize> ArrayLayout<N> {
+    /// 推导把这个布局变换为 `target` 的一串变换。
+    ///
+    /// 两个布局先被变换为规范形式：翻转负步长、去掉长度为 1 或步长为 0 的维度、按步长降序排列，并尝试合并或不合并能合并的维度。
+    /// 然后把目标的每个维度对应到源布局的某个维度上，用切片和分块构造出来，必要时先把目标维度分块再合并。
+    /// 最后沿目标规范化的逆变换还原出目标布局。
+    ///
+    /// 得到的变换会被重放验证，结果与 `target` 的偏移、形状和长度大于 1 的维度的步长都相同。
+    /// 找不到这样的变换（包括 `target` 访问源布局以外的元素，或者没有元素）时返回 `None`。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, Endian::BigEndian, Transform};
+    /// let source = ArrayLayout::<4>::new_contiguous(&[4, 6], BigEndian, 4);
+    /// // 第 1 到 2 行的偶数列，转置后广播
+    /// let target = ArrayLayout::<4>::new(&[3, 5, 2], &[8, 0, 24], 24);
+    ///
+    /// let path = source.derive_path(&target).unwrap();
+    /// let layout = Transform::replay(&path, &source).unwrap();
+    /// assert_eq!(layout.shape(), target.shape());
+    /// assert_eq!(layout.strides(), target.strides());
+    /// assert_eq!(layout.offset(), target.offset());
+    ///
+    /// // 目标访问了源布局以外的元素
+    /// let target = ArrayLayout::<4>::new(&[4, 7], &[28, 4], 0);
+    /// assert!(source.derive_path(&target).is_none());
+    /// ```
+    pub fn derive_path(&self, target: &Self) -> Option<Vec<Transform>> {
+        if self.num_elements() == 0 || target.num_elements() == 0 {
+            return None;
+        }
+        // 合并可能丢失源布局原有的分块，因此依次尝试合并和不合并的规范形式
+        [(false, true), (true, true), (false, false), (true, false)]
+            .into_iter()
+            .find_map(|(merge_source, merge_target)| {
+                let (mut path, source) = canonicalize(self, merge_source);
+                let (target_ops, canonical) = canonicalize(target, merge_target);
+                path.extend(matching(&source, &canonical)?);
+                path.extend(restore(&target_ops, target)?);
+
+                let ans = Transform::replay(&path, self)?;
+                equivalent(&ans, target).then_some(path)
+            })
+    }
+}
+
+/// 判断两个布局是否等价，长度为 1 的维度的步长不影响访问的元素。
+pub(crate) fn equivalent<const N: usize>(a: &ArrayLayout<N>, b: &ArrayLayout<N>) -> bool {
+    fn dims<const N: usize>(l: &ArrayLayout<N>) -> impl Iterator<Item = (usize, isize)> + '_ {
+        zip(l.shape(), l.strides()).map(|(&d, &s)| (d, if d == 1 { 0 } else { s }))
+    }
+    a.offset() == b.offset() && a.ndim() == b.ndim() && dims(a).eq(dims(b))
+}
+
+/// 把布局变换为规范形式，返回变换和规范布局。
+fn canonicalize<const N: usize>(
+    layout: &ArrayLayout<N>,
+    merge: bool,
+) -> (Vec<Transform>, ArrayLayout<N>) {
+    let mut ops = Vec::new();
+    let mut layout = layout.clone();
+    let mut push = |layout: &mut ArrayLayout<N>, op: Transform| {
+        *layout = op.apply(layout).unwrap();
+        ops.push(op)
+    };
+
+    // 翻转负步长
+    for axis in 0..layout.ndim() {
+        let (d, s) = (layout.shape()[axis], layout.strides()[axis]);
+        if d > 1 && s < 0 {
+            let op = Transform::Slice {
+                axis,
+                start: d - 1,
+                step: -1,
+                len: d,
+            };
+            push(&mut layout, op)
+        }
+    }
+    // 去掉长度为 1 和广播的维度
+    for axis in (0..layout.ndim()).rev() {
+        if layout.shape()[axis] == 1 || layout.strides()[axis] == 0 {
+            push(&mut layout, Transform::Index { axis, index: 0 })
+        }
+    }
+    // 按步长降序排列
+    let mut perm = (0..layout.ndim()).collect::<Vec<_>>();
+    perm.sort_by_key(|&i| std::cmp::Reverse(layout.strides()[i]));
+    if perm.iter().enumerate().any(|(i, &j)| i != j) {
+        push(&mut layout, Transform::Transpose { perm })
+    }
+    // 合并相邻的维度
+    for i in (1..layout.ndim()).rev().filter(|_| merge) {
+        let (d, s) = (layout.shape()[i], layout.strides()[i]);
+        if layout.strides()[i - 1] == s * d as isize {
+            push(
+                &mut layout,
+                Transform::MergeBe {
+                    start: i - 1,
+                    len: 2,
+                },
+            )
+        }
+    }
+    (ops, layout)
+}
+
+/// 沿规范化的逆变换从规范布局还原 `target`，把插入和翻转维度改写为基本变换。
+fn restore<const N: usize>(ops: &[Transform], target: &ArrayLayout<N>) -> Option<Vec<Transform>> {
+    let inverse = Transform::invert_chain(ops, target)?;
+    let mut layout = Transform::replay(ops, target)?;
+    let mut ans = Vec::with_capacity(inverse.len());
+    for op in inverse {
+        let ops = match op {
+            Transform::Unindex {
+                axis, len, stride, ..
+            } if layout.ndim() > 0 => {
+                let n = layout.ndim();
+                let tile = if axis < n {
+                    Transform::TileBe {
+                        axis,
+                        tiles: vec![1, layout.shape()[axis]],
+                    }
+                } else {
+                    Transform::TileBe {
+                        axis: n - 1,
+                        tiles: vec![layout.shape()[n - 1], 1],
+                    }
+                };
+                match len {
+                    1 => vec![tile],
+                    times if stride == 0 => vec![tile, Transform::Broadcast { axis, times }],
+                    _ => return None,
+                }
+            }
+            Transform::Unslice {
+                axis,
+                start,
+                step: -1,
+                len,
+            } => vec![Transform::Slice {
+                axis,
+                start,
+                step: -1,
+                len,
+            }],
+            op => vec![op],
+        };
+        for op in ops {
+            layout = op.apply(&layout)?;
+            ans.push(op)
+        }
+    }
+    Some(ans)
+}
+
+/// 用切片和分块把规范的源布局变换为规范的目标布局。
+fn matching<const N: usize>(
+    source: &ArrayLayout<N>,
+    target: &ArrayLayout<N>,
+) -> Option<Vec<Transform>> {
+    let shape = source.shape();
+    let strides = source.strides();
+
+    // 目标偏移在源布局中的起始下标
+    let mut delta = target.offset() - source.offset();
+    let mut starts = vec![0; shape.len()];
+    for (k, (&d, &s)) in zip(shape, strides).enumerate() {
+        if delta < 0 {
+            return None;
+        }
+        starts[k] = (delta / s).min(d as isize - 1) as usize;
+        delta -= starts[k] as isize * s
+    }
+    if delta != 0 {
+        return None;
+    }
+
+    // 把目标维度对应到源维度上，对应不上的目标维度尝试拆开
+    let fits = |d: usize, s: isize| {
+        (0..shape.len()).find(|&k| {
+            s % strides[k] == 0 && starts[k] + (s / strides[k]) as usize * (d - 1) < shape[k]
+        })
+    };
+    fn expand(
+        d: usize,
+        s: isize,
+        fits: &impl Fn(usize, isize) -> Option<usize>,
+    ) -> Option<Vec<(usize, isize)>> {
+        if fits(d, s).is_some() {
+            return Some(vec![(d, s)]);
+        }
+        (2..d).filter(|&f| d.is_multiple_of(f)).find_map(|f| {
+            fits(f, s)?;
+            let mut ans = expand(d / f, s * f as isize, fits)?;
+            ans.push((f, s));
+            Some(ans)
+        })
+    }
+    let mut dims = Vec::new();
+    let mut runs = Vec::new();
+    for (&d, &s) in zip(target.shape(), target.strides()) {
+        let parts = expand(d, s, &fits)?;
+        runs.push((dims.len(), parts.len()));
+        dims.extend(parts)
+    }
+
+    // 每个源维度上的目标维度，按倍数降序排列
+    let mut groups = vec![Vec::new(); shape.len()];
+    for (j, &(d, s)) in dims.iter().enumerate() {
+        let k = fits(d, s).unwrap();
+        groups[k].push((j, (s / strides[k]) as usize, d))
+    }
+    groups
+        .iter_mut()
+        .for_each(|g| g.sort_by_key(|&(_, m, _)| std::cmp::Reverse(m)));
+
+    // 从后向前处理源维度，前面的轴号不变
+    let mut ops = Vec::new();
+    let mut order = Vec::new();
+    for (k, group) in groups.iter().enumerate().rev() {
+        let (d, start) = (shape[k], starts[k]);
+        match **group {
+            [] => ops.push(Transform::Index {
+                axis: k,
+                index: start,
+            }),
+            [(j, m, l)] => {
+                if !(start == 0 && m == 1 && l == d) {
+                    ops.push(Transform::Slice {
+                        axis: k,
+                        start,
+                        step: m as _,
+                        len: l,
+                    })
+                }
+                order.push(vec![j])
+            }
+            [..] => {
+                let muls = group.iter().map(|&(_, m, _)| m).collect::<Vec<_>>();
+                let lens = group.iter().map(|&(_, _, l)| l).collect::<Vec<_>>();
+                let tiled = tile_stepped(k, d, start, &muls, &lens)
+                    .or_else(|| tile_aligned(k, d, start, &muls, &lens))?;
+                ops.extend(tiled);
+                order.push(group.iter().map(|&(j, _, _)| j).collect())
+            }
+        }
+    }
+
+    // 按目标顺序转置，再合并拆开的维度
+    let axes = order.into_iter().rev().flatten().collect::<Vec<_>>();
+    let mut perm = vec![0; axes.len()];
+    for (axis, &j) in axes.iter().enumerate() {
+        perm[j] = axis
+    }
+    if perm.iter().enumerate().any(|(i, &j)| i != j) {
+        ops.push(Transform::Transpose { perm })
+    }
+    for &(start, len) in runs.iter().rev() {
+        if len > 1 {
+            ops.push(Transform::MergeBe { start, len })
+        }
+    }
+    Some(ops)
+}
+
+/// 以最小的倍数为步长从 `start` 开始切片，再按倍数之比分块。
+///
+/// 分块要求切片的长度是整块，因此起点靠后时可能失败。
+fn tile_stepped(
+    axis: usize,
+    d: usize,
+    start: usize,
+    muls: &[usize],
+    lens: &[usize],
+) -> Option<Vec<Transform>> {
+    let last = *muls.last().unwrap();
+    if muls.iter().any(|&m| m % last != 0) {
+        return None;
+    }
+    let muls = muls.iter().map(|&m| m / last).collect::<Vec<_>>();
+    let len = (d - start).div_ceil(last);
+    let mut tiles = vec![len / muls[0]];
+    for w in muls.windows(2) {
+        if w[0] % w[1] != 0 {
+            return None;
+        }
+        tiles.push(w[0] / w[1])
+    }
+    if zip(&tiles, lens).any(|(t, l)| t < l) {
+        return None;
+    }
+
+    let mut ops = vec![
+        Transform::Slice {
+            axis,
+            start,
+            step: last as _,
+            len: tiles[0] * muls[0],
+        },
+        Transform::TileBe {
+            axis,
+            tiles: tiles.clone(),
+        },
+    ];
+    for (a, (&t, &l)) in zip(&tiles, lens).enumerate() {
+        if t != l {
+            ops.push(Transform::Slice {
+                axis: axis + a,
+                start: 0,
+                step: 1,
+                len: l,
+            })
+        }
+    }
+    Some(ops)
+}
+
+/// 从 0 开始按倍数分块，起点分解到各个块中，最低的块被索引掉。
+fn tile_aligned(
+    axis: usize,
+    d: usize,
+    start: usize,
+    muls: &[usize],
+    lens: &[usize],
+) -> Option<Vec<Transform>> {
+    let mut tiles = vec![d / muls[0]];
+    for w in muls.windows(2) {
+        if w[0] % w[1] != 0 {
+            return None;
+        }
+        tiles.push(w[0] / w[1])
+    }
+    let last = *muls.last().unwrap();
+    if last > 1 {
+        tiles.push(last)
+    }
+    let len = tiles.iter().product::<usize>();
+    if len == 0 || start >= len {
+        return None;
+    }
+
+    // 起点的混合进制表示
+    let mut digits = vec![0; tiles.len()];
+    let mut rem = start;
+    for (digit, &t) in zip(&mut digits, &tiles).rev() {
+        *digit = rem % t;
+        rem /= t
+    }
+    if zip(zip(&digits, &tiles), lens).any(|((&i, &t), &l)| i + l > t) {
+        return None;
+    }
+
+    let mut ops = Vec::new();
+    if len != d {
+        ops.push(Transform::Slice {
+            axis,
+            start: 0,
+            step: 1,
+            len,
+        })
+    }
+    ops.push(Transform::TileBe {
+        axis,
+        tiles: tiles.clone(),
+    });
+    for (a, ((&i, &t), &l)) in zip(zip(&digits, &tiles), lens).enumerate() {
+        if i != 0 || t != l {
+            ops.push(Transform::Slice {
+                axis: axis + a,
+                start: i,
+                step: 1,
+                len: l,
+            })
+        }
+    }
+    if last > 1 {
+        ops.push(Transform::Index {
+            axis: axis + lens.len(),
+            index: digits[lens.len()],
+        })
+    }
+    Some(ops)
+}
+
+#[test]
+fn test() {
+    use crate::Endian::BigEndian;
+
+    let check = |source: &ArrayLayout<4>, target: &ArrayLayout<4>| {
+        let path = source.derive_path(target).unwrap();
+        assert!(equivalent(
+            &Transform::replay(&path, source).unwrap(),
+            target
+        ));
+    };
+
+    let source = ArrayLayout::<4>::new_contiguous(&[2, 3, 4], BigEndian, 4);
+    let targets = [
+        source.clone(),
+        source.transpose(&[2, 0]).merge_free(0, 2).unwrap(),
+        source.merge_be(0, 3).unwrap().tile_le(0, &[4, 6]),
+        source.slice(2, 3, -2, 2).index(1, 1),
+        source
+            .slice(0, 1, 1, 1)
+            .broadcast(0, 5)
+            .transpose(&[2, 0, 1]),
+        source.index(0, 1).index(0, 2).index(0, 3),
+        source
+            .merge_be(1, 2)
+            .unwrap()
+            .slice(1, 2, 3, 4)
+            .slice(0, 1, 1, 1),
+        ArrayLayout::<4>::new(&[2, 2, 1], &[-48, 8, 100], 52),
+    ];
+    for target in &targets {
+        check(&source, target)
+    }
+
+    // 需要把目标维度拆开再合并
+    let source = ArrayLayout::<4>::new(&[2, 4], &[12, 3], 0);
+    check(&source, &ArrayLayout::new(&[4], &[6], 0));
+    check(&source, &source.transpose(&[1, 0]).slice(0, 1, 2, 2));
+
+    // 不存在的路径
+    let source = ArrayLayout::<4>::new_contiguous(&[2, 3], BigEndian, 1);
+    for target in [
+        ArrayLayout::<4>::new(&[3], &[1], 4),
+        ArrayLayout::<4>::new(&[2], &[2], -1),
+        ArrayLayout::<4>::new(&[7], &[1], 0),
+        ArrayLayout::<4>::new(&[0], &[1], 0),
+    ] {
+        assert!(source.derive_path(&target).is_none());
+    }
+}
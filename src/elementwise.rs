@@ -0,0 +1,218 @@
+use crate::ArrayLayout;
+
+/// 多操作数逐元素运算的迭代计划。
+///
+/// 所有操作数共享同一个迭代形状，按大端序遍历，即最后一维是最内层循环。
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ElementwisePlan {
+    shape: Vec<usize>,
+    strides: Vec<isize>,
+    offsets: Vec<isize>,
+    block: usize,
+}
+
+impl ElementwisePlan {
+    /// 最内层循环分块时每块数据量的目标字节数。
+    pub const BLOCK_BYTES: usize = 16 << 10;
+
+    /// 共享的迭代形状。
+    #[inline]
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// 迭代的维数。
+    #[inline]
+    pub fn ndim(&self) -> usize {
+        self.shape.len()
+    }
+
+    /// 操作数的数量。
+    #[inline]
+    pub fn num_operands(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// 第 `operand` 个操作数在迭代形状上的步长。
+    #[inline]
+    pub fn strides(&self, operand: usize) -> &[isize] {
+        let ndim = self.ndim();
+        &self.strides[operand * ndim..][..ndim]
+    }
+
+    /// 第 `operand` 个操作数的起始偏移。
+    #[inline]
+    pub fn offset(&self, operand: usize) -> isize {
+        self.offsets[operand]
+    }
+
+    /// 最内层循环的分块大小，迭代形状为空或元素数为 0 时为 0。
+    #[inline]
+    pub fn block(&self) -> usize {
+        self.block
+    }
+
+    /// 将第 `operand` 个操作数的迭代方式表示为布局。
+    #[inline]
+    pub fn layout<const N: usize>(&self, operand: usize) -> ArrayLayout<N> {
+        ArrayLayout::new(&self.shape, self.strides(operand), self.offset(operand))
+    }
+}
+
+impl<const N: usize> ArrayLayout<N> {
+    /// 为形状相同的多个操作数规划逐元素运算的循环顺序和分块。
+    ///
+    /// 规划去除长度为 1 的维度，翻转所有操作数步长都非正的维度，
+    /// 按各操作数以元素计的步长之和从大到小重排维度，
+    /// 然后合并对所有操作数都连续的相邻维度，最后为最内层循环选择分块大小。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, BroadcastArg, Endian::BigEndian};
+    /// let a = ArrayLayout::<3>::new_contiguous(&[4, 8, 16], BigEndian, 4);
+    /// let b = ArrayLayout::<3>::new(&[1, 1, 16], &[0, 0, 2], 0).broadcast_many(&[
+    ///     BroadcastArg { axis: 0, times: 4 },
+    ///     BroadcastArg { axis: 1, times: 8 },
+    /// ]);
+    ///
+    /// let plan = ArrayLayout::plan_elementwise(&[&a, &b], &[4, 2]);
+    /// assert_eq!(plan.shape(), &[32, 16]);
+    /// assert_eq!(plan.strides(0), &[64, 4]);
+    /// assert_eq!(plan.strides(1), &[0, 2]);
+    /// assert_eq!(plan.block(), 16);
+    ///
+    /// // 转置过的操作数恢复为存储顺序
+    /// let plan = ArrayLayout::plan_elementwise(&[&a.transpose(&[2, 0, 1])], &[4]);
+    /// assert_eq!(plan.shape(), &[512]);
+    /// assert_eq!(plan.strides(0), &[4]);
+    /// ```
+    pub fn plan_elementwise(layouts: &[&Self], element_sizes: &[usize]) -> ElementwisePlan {
+        assert_eq!(
+            layouts.len(),
+            element_sizes.len(),
+            "layouts and element sizes must have the same length"
+        );
+        let [first, tail @ ..] = layouts else {
+            panic!("at least one operand is required")
+        };
+        let shape = first.shape();
+        for layout in tail {
+            assert_eq!(layout.shape(), shape, "operands must have the same shape");
+        }
+
+        let n = layouts.len();
+        let mut offsets = layouts.iter().map(|l| l.offset()).collect::<Vec<_>>();
+
+        if shape.contains(&0) {
+            return ElementwisePlan {
+                shape: vec![0],
+                strides: vec![0; n],
+                offsets,
+                block: 0,
+            };
+        }
+
+        // 每个维度：(长度, 各操作数的步长)
+        let mut axes = Vec::with_capacity(shape.len());
+        for (i, &d) in shape.iter().enumerate() {
+            if d == 1 {
+                continue;
+            }
+            let mut strides = layouts.iter().map(|l| l.strides()[i]).collect::<Vec<_>>();
+            if strides.iter().all(|&s| s <= 0) && strides.iter().any(|&s| s < 0) {
+                for (offset, s) in offsets.iter_mut().zip(&mut strides) {
+                    *offset += (d - 1) as isize * *s;
+                    *s = -*s
+                }
+            }
+            axes.push((d, strides))
+        }
+
+        let cost = |strides: &[isize]| {
+            strides
+                .iter()
+                .zip(element_sizes)
+                .map(|(&s, &es)| s.unsigned_abs() / es.max(1))
+                .sum::<usize>()
+        };
+        axes.sort_by_key(|(_, strides)| std::cmp::Reverse(cost(strides)));
+
+        let mut merged: Vec<(usize, Vec<isize>)> = Vec::with_capacity(axes.len());
+        for (d, strides) in axes {
+            match merged.last_mut() {
+                Some((d_, strides_))
+                    if strides_
+                        .iter()
+                        .zip(&strides)
+                        .all(|(&outer, &inner)| outer == inner * d as isize) =>
+                {
+                    *d_ *= d;
+                    *strides_ = strides
+                }
+                _ => merged.push((d, strides)),
+            }
+        }
+
+        let bytes = element_sizes.iter().sum::<usize>().max(1);
+        let block = merged.last().map_or(0, |&(d, _)| {
+            (ElementwisePlan::BLOCK_BYTES / bytes).clamp(1, d)
+        });
+
+        let ndim = merged.len();
+        let mut strides = vec![0; n * ndim];
+        for (i, (_, s)) in merged.iter().enumerate() {
+            for (j, &s) in s.iter().enumerate() {
+                strides[j * ndim + i] = s
+            }
+        }
+        ElementwisePlan {
+            shape: merged.into_iter().map(|(d, _)| d).collect(),
+            strides,
+            offsets,
+            block,
+        }
+    }
+}
+
+#[test]
+fn test() {
+    use crate::Endian::BigEndian;
+
+    // 全部连续的操作数合并为一维，分块受字节预算限制
+    let a = ArrayLayout::<3>::new_contiguous(&[64, 1, 256], BigEndian, 4);
+    let b = a.clone();
+    let plan = ArrayLayout::plan_elementwise(&[&a, &b], &[4, 4]);
+    assert_eq!(plan.shape(), &[64 * 256]);
+    assert_eq!(plan.num_operands(), 2);
+    assert_eq!(plan.block(), ElementwisePlan::BLOCK_BYTES / 8);
+    assert!(plan.layout::<3>(1) == a.merge_be(0, 3).unwrap());
+
+    // 所有操作数都反向的维度被翻转，广播维度放在最外层
+    let a = ArrayLayout::<3>::new_contiguous(&[5, 6], BigEndian, 1).slice(1, 5, -1, 6);
+    let b = ArrayLayout::<3>::new(&[1, 6], &[0, -2], 10).broadcast(0, 5);
+    let plan = ArrayLayout::plan_elementwise(&[&a, &b], &[1, 2]);
+    assert_eq!(plan.shape(), &[5, 6]);
+    assert_eq!(plan.strides(0), &[6, 1]);
+    assert_eq!(plan.strides(1), &[0, 2]);
+    assert_eq!(plan.offset(0), 0);
+    assert_eq!(plan.offset(1), 0);
+
+    // 计划遍历的元素集合与原布局一致
+    let mut expected = (0..30)
+        .map(|i| a.element_offset(i, BigEndian))
+        .collect::<Vec<_>>();
+    let layout = plan.layout::<2>(0);
+    let mut actual = (0..30)
+        .map(|i| layout.element_offset(i, BigEndian))
+        .collect::<Vec<_>>();
+    expected.sort_unstable();
+    actual.sort_unstable();
+    assert_eq!(expected, actual);
+
+    // 空数组和标量
+    let empty = ArrayLayout::<2>::new_contiguous(&[3, 0], BigEndian, 4);
+    let plan = ArrayLayout::plan_elementwise(&[&empty], &[4]);
+    assert_eq!((plan.shape(), plan.block()), (&[0][..], 0));
+    let scalar = ArrayLayout::<2>::new(&[], &[], 8);
+    let plan = ArrayLayout::plan_elementwise(&[&scalar], &[4]);
+    assert_eq!((plan.ndim(), plan.offset(0), plan.block()), (0, 8, 0));
+}
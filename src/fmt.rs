@@ -1,5 +1,5 @@
-﻿use crate::ArrayLayout;
-use std::fmt;
+﻿use crate::{ArrayLayout, TensorView};
+use std::{fmt, iter::zip};
 
 impl<const N: usize> ArrayLayout<N> {
     /// 高维数组格式化。
@@ -12,79 +12,525 @@ impl<const N: usize> ArrayLayout<N> {
         f: &mut fmt::Formatter,
         ptr: *const T,
     ) -> fmt::Result {
-        match self.ndim() {
-            0 => {
-                write!(f, "array<> = [{}]", unsafe {
-                    ptr.byte_offset(self.offset()).read_unaligned()
-                })
+        unsafe { self.write_titled(f, &title(self.shape()), ptr) }
+    }
+
+    /// 以给定的标题格式化高维数组。
+    pub(crate) unsafe fn write_titled<T: fmt::Display + Copy>(
+        &self,
+        f: &mut fmt::Formatter,
+        title: &str,
+        ptr: *const T,
+    ) -> fmt::Result {
+        let strides = self.strides();
+        let ptr = unsafe { ptr.byte_offset(self.offset()) };
+        write_nd(f, title, self.shape(), |indices| {
+            let offset = zip(indices, strides)
+                .map(|(&i, &s)| i as isize * s)
+                .sum::<isize>();
+            unsafe { ptr.byte_offset(offset).read_unaligned() }
+        })
+    }
+}
+
+impl<const N: usize> ArrayLayout<N> {
+    /// 高维数组格式化，每个元素的字节交给 `formatter` 格式化。
+    ///
+    /// 布局的偏移和步长以字节为单位，访问的范围超出 `data` 时 panic。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, Endian::BigEndian, F16, Hex};
+    /// use std::fmt;
+    ///
+    /// struct Tensor<'a>(ArrayLayout<2>, &'a [u8]);
+    /// impl fmt::Display for Tensor<'_> {
+    ///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    ///         self.0.write_array_with(f, self.1, 2, F16)?;
+    ///         self.0.write_array_with(f, self.1, 2, Hex)?;
+    ///         // 闭包也可以作为格式化器
+    ///         self.0.write_array_with(f, self.1, 2, |f: &mut fmt::Formatter, b: &[u8]| {
+    ///             write!(f, "{}", b.len())
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// let data = [0x00, 0x3c, 0x00, 0xc0, 0x00, 0x7c];
+    /// let layout = ArrayLayout::<2>::new_contiguous(&[3], BigEndian, 2);
+    /// assert_eq!(
+    ///     Tensor(layout, &data).to_string(),
+    ///     "\
+    /// array<3>[\n    1\n    -2\n    inf\n]
+    /// array<3>[\n    0x3c00\n    0xc000\n    0x7c00\n]
+    /// array<3>[\n    2\n    2\n    2\n]\n",
+    /// );
+    /// ```
+    pub fn write_array_with(
+        &self,
+        f: &mut fmt::Formatter,
+        data: &[u8],
+        element_size: usize,
+        formatter: impl FormatElement,
+    ) -> fmt::Result {
+        if self.num_elements() > 0 {
+            let range = self.data_range();
+            assert!(
+                *range.start() >= 0 && *range.end() as usize + element_size <= data.len(),
+                "range {range:?} out of {} bytes",
+                data.len(),
+            );
+        }
+        let strides = self.strides();
+        write_nd(f, &title(self.shape()), self.shape(), |indices| {
+            let offset = zip(indices, strides)
+                .map(|(&i, &s)| i as isize * s)
+                .sum::<isize>()
+                + self.offset();
+            let offset = offset as usize;
+            Element {
+                bytes: &data[offset..][..element_size],
+                formatter: &formatter,
             }
-            1 => {
-                let &[n] = self.shape() else { unreachable!() };
-                let &[s] = self.strides() else { unreachable!() };
-
-                writeln!(f, "array<{n}>[")?;
-                let ptr = unsafe { ptr.byte_offset(self.offset()) };
-                for i in 0..n as isize {
-                    writeln!(f, "    {}", unsafe {
-                        ptr.byte_offset(i * s).read_unaligned()
-                    })?
-                }
-                writeln!(f, "]")?;
-                Ok(())
+        })
+    }
+}
+
+/// 从元素的字节格式化元素。
+pub trait FormatElement {
+    /// 格式化一个元素，`bytes` 是它在内存中的字节。
+    fn fmt_element(&self, f: &mut fmt::Formatter, bytes: &[u8]) -> fmt::Result;
+}
+
+impl<F: Fn(&mut fmt::Formatter, &[u8]) -> fmt::Result> FormatElement for F {
+    #[inline]
+    fn fmt_element(&self, f: &mut fmt::Formatter, bytes: &[u8]) -> fmt::Result {
+        self(f, bytes)
+    }
+}
+
+/// 把元素的字节按小端序解释为整数，以十六进制打印。
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Hex;
+
+/// 把 2 字节的元素按小端序解释为 IEEE 754 半精度浮点数打印。
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct F16;
+
+/// 把 2 字节的元素按小端序解释为 bfloat16 打印。
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Bf16;
+
+/// 把元素按是否有非零字节打印为 `true` 或 `false`。
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Bool;
+
+impl FormatElement for Hex {
+    fn fmt_element(&self, f: &mut fmt::Formatter, bytes: &[u8]) -> fmt::Result {
+        write!(f, "0x")?;
+        for b in bytes.iter().rev() {
+            write!(f, "{b:02x}")?
+        }
+        Ok(())
+    }
+}
+
+impl FormatElement for F16 {
+    fn fmt_element(&self, f: &mut fmt::Formatter, bytes: &[u8]) -> fmt::Result {
+        let bits = u16::from_le_bytes([bytes[0], bytes[1]]) as u32;
+        let sign = (bits >> 15) << 31;
+        let exp = (bits >> 10) & 0x1f;
+        let frac = bits & 0x3ff;
+        let val = match exp {
+            // 非规格化数
+            0 => (frac as f32 / (1 << 24) as f32).copysign(f32::from_bits(sign | 0x3f80_0000)),
+            // 无穷大和 NaN
+            0x1f => f32::from_bits(sign | 0x7f80_0000 | (frac << 13)),
+            _ => f32::from_bits(sign | ((exp + 127 - 15) << 23) | (frac << 13)),
+        };
+        fmt::Display::fmt(&val, f)
+    }
+}
+
+impl FormatElement for Bf16 {
+    fn fmt_element(&self, f: &mut fmt::Formatter, bytes: &[u8]) -> fmt::Result {
+        let bits = u16::from_le_bytes([bytes[0], bytes[1]]) as u32;
+        fmt::Display::fmt(&f32::from_bits(bits << 16), f)
+    }
+}
+
+impl FormatElement for Bool {
+    fn fmt_element(&self, f: &mut fmt::Formatter, bytes: &[u8]) -> fmt::Result {
+        fmt::Display::fmt(&bytes.iter().any(|&b| b != 0), f)
+    }
+}
+
+/// 用格式化器打印的单个元素。
+struct Element<'a, F> {
+    bytes: &'a [u8],
+    formatter: &'a F,
+}
+
+impl<F: FormatElement> fmt::Display for Element<'_, F> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.formatter.fmt_element(f, self.bytes)
+    }
+}
+
+/// 生成形如 `array<2x3x4>` 的标题。
+pub(crate) fn title(shape: &[usize]) -> String {
+    let shape = shape.iter().map(|d| d.to_string()).collect::<Vec<_>>();
+    format!("array<{}>", shape.join("x"))
+}
+
+/// 按形状遍历多维数组，用 `get` 获取每个下标处的元素并格式化。
+pub(crate) fn write_nd<T: fmt::Display>(
+    f: &mut fmt::Formatter,
+    title: &str,
+    shape: &[usize],
+    mut get: impl FnMut(&[usize]) -> T,
+) -> fmt::Result {
+    match *shape {
+        [] => write!(f, "{title} = [{}]", get(&[])),
+        [n] => {
+            writeln!(f, "{title}[")?;
+            for i in 0..n {
+                writeln!(f, "    {}", get(&[i]))?
             }
-            _ => {
-                let mut title = "array<".to_string();
-                for d in self.shape() {
-                    title.push_str(&format!("{d}x"))
-                }
-                assert_eq!(title.pop(), Some('x'));
-                title.push('>');
+            writeln!(f, "]")?;
+            Ok(())
+        }
+        [..] => {
+            let mut indices = Vec::with_capacity(shape.len());
+            write_recursive(f, title, shape, &mut indices, &mut get)
+        }
+    }
+}
 
-                let mut stack = Vec::with_capacity(self.ndim() - 2);
-                self.write_recursive(f, ptr, &title, &mut stack)
+fn write_recursive<T: fmt::Display>(
+    f: &mut fmt::Formatter,
+    title: &str,
+    shape: &[usize],
+    indices: &mut Vec<usize>,
+    get: &mut impl FnMut(&[usize]) -> T,
+) -> fmt::Result {
+    match *shape {
+        [] | [_] => unreachable!(),
+        [rows, cols] => {
+            write!(f, "{title}[")?;
+            for i in &*indices {
+                write!(f, "{i}, ")?
+            }
+            writeln!(f, "..]")?;
+
+            for r in 0..rows {
+                indices.push(r);
+                for c in 0..cols {
+                    indices.push(c);
+                    write!(f, "{} ", get(indices))?;
+                    indices.pop();
+                }
+                indices.pop();
+                writeln!(f)?
+            }
+        }
+        [batch, ref tail @ ..] => {
+            for i in 0..batch {
+                indices.push(i);
+                write_recursive(f, title, tail, indices, get)?;
+                indices.pop();
             }
         }
     }
+    Ok(())
+}
+
+/// 数组打印的风格。
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Style {
+    /// 按二维块打印，每块带有形如 `array<2x3x4>[0, ..]` 的标题，与 [`ArrayLayout::write_array`] 相同。
+    Block,
+    /// 与 NumPy 相同的嵌套方括号风格。
+    Numpy,
+}
+
+/// 数组打印的参数。
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct PrintOptions {
+    /// 元素总数超过这个值时省略每个维度中间的元素。
+    pub threshold: usize,
+    /// 省略时每个维度两端保留的元素数。
+    pub edgeitems: usize,
+    /// 浮点数的小数位数，`None` 表示使用 `{}` 的默认格式。
+    pub precision: Option<usize>,
+    /// 每个元素的最小宽度，所有元素按最宽的元素右对齐。
+    pub width: usize,
+    /// 每行的最大字符数，超出时换行。
+    pub linewidth: usize,
+    /// 打印风格。
+    pub style: Style,
+}
+
+impl Default for PrintOptions {
+    /// 默认参数与 NumPy 相同：超过 1000 个元素时省略，两端保留 3 个元素，每行 75 个字符，使用块风格。
+    #[inline]
+    fn default() -> Self {
+        Self {
+            threshold: 1000,
+            edgeitems: 3,
+            precision: None,
+            width: 0,
+            linewidth: 75,
+            style: Style::Block,
+        }
+    }
+}
 
-    fn write_recursive<T: fmt::Display>(
+impl PrintOptions {
+    /// 按参数格式化高维数组。
+    ///
+    /// # Safety
+    ///
+    /// 这个函数从对裸指针解引用以获得要格式化的数组元素。
+    pub unsafe fn write_array<T: fmt::Display + Copy, const N: usize>(
         &self,
         f: &mut fmt::Formatter,
+        layout: &ArrayLayout<N>,
         ptr: *const T,
+    ) -> fmt::Result {
+        let strides = layout.strides();
+        let ptr = unsafe { ptr.byte_offset(layout.offset()) };
+        self.write_nd(f, &title(layout.shape()), layout.shape(), |indices| {
+            let offset = zip(indices, strides)
+                .map(|(&i, &s)| i as isize * s)
+                .sum::<isize>();
+            unsafe { ptr.byte_offset(offset).read_unaligned() }
+        })
+    }
+
+    /// 按参数遍历多维数组，用 `get` 获取每个可见下标处的元素并格式化。
+    pub(crate) fn write_nd<T: fmt::Display>(
+        &self,
+        f: &mut fmt::Formatter,
         title: &str,
-        indices: &mut Vec<usize>,
+        shape: &[usize],
+        mut get: impl FnMut(&[usize]) -> T,
     ) -> fmt::Result {
-        match *self.shape() {
-            [] | [_] => unreachable!(),
-            [rows, cols] => {
-                write!(f, "{title}[")?;
-                for i in indices {
-                    write!(f, "{i}, ")?
+        // 每个维度上可见的下标，`None` 表示省略号
+        let summarize = shape.iter().product::<usize>() > self.threshold;
+        let axes = shape
+            .iter()
+            .map(|&d| {
+                if summarize && d > 2 * self.edgeitems {
+                    let head = (0..self.edgeitems).map(Some);
+                    let tail = (d - self.edgeitems..d).map(Some);
+                    head.chain([None]).chain(tail).collect()
+                } else {
+                    (0..d).map(Some).collect::<Vec<_>>()
                 }
-                writeln!(f, "..]")?;
-
-                let &[rs, cs] = self.strides() else {
-                    unreachable!()
-                };
-
-                let ptr = unsafe { ptr.byte_offset(self.offset()) };
-                for r in 0..rows as isize {
-                    for c in 0..cols as isize {
-                        write!(f, "{} ", unsafe {
-                            ptr.byte_offset(r * rs + c * cs).read_unaligned()
-                        })?
+            })
+            .collect::<Vec<_>>();
+
+        // 按字典序格式化所有可见元素，渲染时以相同的顺序取出
+        let mut cells = Vec::new();
+        let mut indices = Vec::with_capacity(shape.len());
+        visit(&axes, &mut indices, &mut |indices| {
+            let x = get(indices);
+            cells.push(match self.precision {
+                Some(p) => format!("{x:.p$}"),
+                None => x.to_string(),
+            })
+        });
+        let width = cells.iter().map(String::len).fold(self.width, usize::max);
+        let mut cells = cells.into_iter().map(|s| format!("{s:>width$}"));
+
+        let mut out = Printer {
+            buf: String::new(),
+            linewidth: self.linewidth,
+        };
+        match self.style {
+            Style::Block => match &*axes {
+                [] => out.push(&format!("{title} = [{}]", cells.next().unwrap())),
+                [axis] => {
+                    out.push(&format!("{title}[\n"));
+                    for i in axis {
+                        let cell = i.map_or_else(|| "...".into(), |_| cells.next().unwrap());
+                        out.push(&format!("    {cell}\n"))
                     }
-                    writeln!(f)?
+                    out.push("]\n")
                 }
+                [..] => write_block(&mut out, title, &axes, &mut Vec::new(), &mut cells),
+            },
+            Style::Numpy => write_numpy(&mut out, &axes, 0, &mut cells),
+        }
+        f.write_str(&out.buf)
+    }
+
+    /// 绑定视图，得到无需 `unsafe` 即可格式化的包装。
+    #[inline]
+    pub fn bind<'a, T, const N: usize>(self, view: &TensorView<'a, T, N>) -> Printed<'a, T, N> {
+        Printed {
+            view: view.clone(),
+            options: self,
+        }
+    }
+}
+
+/// 按 [`PrintOptions`] 格式化视图的包装，同时实现 [`Display`](fmt::Display) 和 [`Debug`](fmt::Debug)。
+///
+/// ```rust
+/// # use ndarray_layout::{ArrayLayout, Endian::BigEndian, PrintOptions, Style, TensorView};
+/// let data = (0..2000).map(|x| x as f32 / 4.).collect::<Vec<_>>();
+/// let layout = ArrayLayout::<4>::new_contiguous(&[40, 50], BigEndian, 4);
+/// let view = TensorView::new(layout, &data).unwrap();
+/// let options = PrintOptions {
+///     edgeitems: 2,
+///     precision: Some(2),
+///     style: Style::Numpy,
+///     ..Default::default()
+/// };
+/// assert_eq!(
+///     options.bind(&view).to_string(),
+///     "\
+/// [[  0.00   0.25 ...  12.00  12.25]
+///  [ 12.50  12.75 ...  24.50  24.75]
+///  ...
+///  [475.00 475.25 ... 487.00 487.25]
+///  [487.50 487.75 ... 499.50 499.75]]",
+/// );
+/// ```
+pub struct Printed<'a, T, const N: usize> {
+    view: TensorView<'a, T, N>,
+    options: PrintOptions,
+}
+
+impl<T: fmt::Display + Copy, const N: usize> fmt::Display for Printed<'_, T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let shape = self.view.layout().shape();
+        self.options.write_nd(f, &title(shape), shape, |indices| {
+            *self.view.get(indices).unwrap()
+        })
+    }
+}
+
+impl<T: fmt::Display + Copy, const N: usize> fmt::Debug for Printed<'_, T, N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// 按字典序访问所有可见下标。
+fn visit(axes: &[Vec<Option<usize>>], indices: &mut Vec<usize>, f: &mut impl FnMut(&[usize])) {
+    match axes {
+        [] => f(indices),
+        [axis, tail @ ..] => {
+            for &i in axis.iter().flatten() {
+                indices.push(i);
+                visit(tail, indices, f);
+                indices.pop();
             }
-            [batch, ..] => {
-                for i in 0..batch {
-                    indices.push(i);
-                    self.index(0, i).write_recursive(f, ptr, title, indices)?;
-                    indices.pop();
+        }
+    }
+}
+
+/// 按行宽换行的输出缓冲。
+struct Printer {
+    buf: String,
+    linewidth: usize,
+}
+
+impl Printer {
+    #[inline]
+    fn push(&mut self, s: &str) {
+        self.buf.push_str(s)
+    }
+
+    /// 在当前行追加以 `sep` 分隔的 `token`，当前行放不下时换行并缩进 `indent` 个空格。
+    fn push_token(&mut self, sep: &str, token: &str, reserve: usize, indent: usize) {
+        let line = self.buf.len() - self.buf.rfind('\n').map_or(0, |i| i + 1);
+        if line > indent && line + sep.len() + token.len() + reserve > self.linewidth {
+            self.buf.push('\n');
+            self.buf.extend(std::iter::repeat_n(' ', indent));
+        } else {
+            self.buf.push_str(sep)
+        }
+        self.buf.push_str(token)
+    }
+}
+
+fn write_block(
+    out: &mut Printer,
+    title: &str,
+    axes: &[Vec<Option<usize>>],
+    indices: &mut Vec<usize>,
+    cells: &mut impl Iterator<Item = String>,
+) {
+    match axes {
+        [rows, cols] => {
+            out.push(&format!("{title}["));
+            for i in &*indices {
+                out.push(&format!("{i}, "))
+            }
+            out.push("..]\n");
+
+            for r in rows {
+                if r.is_none() {
+                    out.push("...\n");
+                    continue;
+                }
+                for (k, c) in cols.iter().enumerate() {
+                    let cell = c.map_or_else(|| "...".into(), |_| cells.next().unwrap());
+                    out.push_token(if k == 0 { "" } else { " " }, &cell, 1, 0)
                 }
+                out.push(" \n")
             }
         }
-        Ok(())
+        [batch, tail @ ..] => {
+            for i in batch {
+                match *i {
+                    Some(i) => {
+                        indices.push(i);
+                        write_block(out, title, tail, indices, cells);
+                        indices.pop();
+                    }
+                    None => out.push("...\n"),
+                }
+            }
+        }
+        [] => unreachable!(),
+    }
+}
+
+fn write_numpy(
+    out: &mut Printer,
+    axes: &[Vec<Option<usize>>],
+    depth: usize,
+    cells: &mut impl Iterator<Item = String>,
+) {
+    match axes {
+        [] => out.push(&cells.next().unwrap()),
+        [axis] => {
+            out.push("[");
+            for (k, i) in axis.iter().enumerate() {
+                let cell = i.map_or_else(|| "...".into(), |_| cells.next().unwrap());
+                out.push_token(if k == 0 { "" } else { " " }, &cell, depth + 1, depth + 1)
+            }
+            out.push("]")
+        }
+        [axis, tail @ ..] => {
+            out.push("[");
+            for (k, i) in axis.iter().enumerate() {
+                if k > 0 {
+                    out.push(&"\n".repeat(tail.len()));
+                    out.push(&" ".repeat(depth + 1))
+                }
+                match i {
+                    Some(_) => write_numpy(out, tail, depth + 1, cells),
+                    None => out.push("..."),
+                }
+            }
+            out.push("]")
+        }
     }
 }
 
@@ -112,4 +558,98 @@ fn test() {
 
     let tensor = Tensor(tensor.0.tile_be(0, &[2, 3]).tile_be(2, &[5, 2]));
     println!("{}", tensor);
+
+    // 默认参数下小数组的块风格与 write_array 相同
+    struct Options(ArrayLayout<4>, PrintOptions);
+
+    impl fmt::Display for Options {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            unsafe { self.1.write_array(f, &self.0, DATA.as_ptr()) }
+        }
+    }
+
+    let options = PrintOptions::default();
+    for layout in [
+        ArrayLayout::<4>::new(&[], &[], 3),
+        ArrayLayout::<4>::new_contiguous(&[DATA.len()], crate::Endian::BigEndian, 1),
+        tensor.0.clone(),
+    ] {
+        assert_eq!(
+            Options(layout.clone(), options).to_string(),
+            Tensor(layout).to_string()
+        );
+    }
+
+    // 省略、对齐和换行
+    let layout = ArrayLayout::<4>::new(&[3, 4, 10], &[0, 0, 1], 0);
+    let options = PrintOptions {
+        threshold: 10,
+        edgeitems: 1,
+        width: 2,
+        ..options
+    };
+    assert_eq!(
+        Options(layout.clone(), options).to_string(),
+        "\
+array<3x4x10>[0, ..]
+ 1 ...  0 \n\
+...
+ 1 ...  0 \n\
+...
+array<3x4x10>[2, ..]
+ 1 ...  0 \n\
+...
+ 1 ...  0 \n"
+    );
+    let options = PrintOptions {
+        threshold: usize::MAX,
+        linewidth: 12,
+        style: Style::Numpy,
+        ..options
+    };
+    assert_eq!(
+        Options(layout.index(0, 0).slice(0, 0, 1, 2), options).to_string(),
+        "\
+[[ 1  2  3
+   4  5  6
+   7  8  9
+   0]
+ [ 1  2  3
+   4  5  6
+   7  8  9
+   0]]"
+    );
+
+    // 自定义格式化器
+    struct Bytes(ArrayLayout<4>, &'static [u8], usize);
+
+    impl fmt::Display for Bytes {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self.2 {
+                0 => self.0.write_array_with(f, self.1, 2, Bf16),
+                1 => self.0.write_array_with(f, self.1, 2, F16),
+                _ => self.0.write_array_with(f, self.1, 1, Bool),
+            }
+        }
+    }
+
+    const HALF: &[u8] = &[0x80, 0x3f, 0x01, 0x00, 0x00, 0x80, 0xff, 0x7b];
+    let layout = ArrayLayout::<4>::new_contiguous(&[2, 2], crate::Endian::BigEndian, 2);
+    assert_eq!(
+        Bytes(layout.clone(), HALF, 0).to_string(),
+        format!(
+            "array<2x2>[..]\n1 {} \n-0 {} \n",
+            f32::from_bits(0x10000),
+            f32::from_bits(0x7bff0000)
+        )
+    );
+    assert_eq!(
+        Bytes(layout.transpose(&[1, 0]), HALF, 1).to_string(),
+        "array<2x2>[..]\n1.875 -0 \n0.000000059604645 65504 \n"
+    );
+    let layout = ArrayLayout::<4>::new_contiguous(&[4], crate::Endian::BigEndian, 2);
+    assert_eq!(
+        Bytes(layout, HALF, 2).to_string(),
+        "array<4>[\n    true\n    true\n    false\n    true\n]\n"
+    );
 }
@@ -0,0 +1,226 @@
+use crate::ArrayLayout;
+
+/// BLAS 风格的矩阵操作数描述。
+///
+/// BLAS 按列主序解释矩阵：不转置时元素 `(r, c)` 位于 `offset + (r + c * ld) * element_size`，
+/// 转置时位于 `offset + (c + r * ld) * element_size`。
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct GemmOperand {
+    /// 批次数，2 维布局为 1。
+    pub batch: usize,
+    /// 逻辑行数。
+    pub rows: usize,
+    /// 逻辑列数。
+    pub cols: usize,
+    /// 是否需要转置，即数据是否按行主序存储。
+    pub trans: bool,
+    /// 主维度，以元素为单位。
+    pub ld: usize,
+    /// 批次间的步长，以元素为单位，广播的批次为 0。
+    pub batch_stride: isize,
+    /// 第一个元素的偏移，以字节为单位。
+    pub offset: isize,
+}
+
+/// 批量矩阵乘 `C = A · B` 的调用方案。
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct GemmPlan {
+    /// 是否交换操作数，计算 `Cᵀ = Bᵀ · Aᵀ`。
+    pub swap: bool,
+    /// 批次数。
+    pub batch: usize,
+    /// BLAS 调用的 `m`。
+    pub m: usize,
+    /// BLAS 调用的 `n`。
+    pub n: usize,
+    /// BLAS 调用的 `k`。
+    pub k: usize,
+    /// BLAS 调用的左操作数，交换时为 `Bᵀ`。
+    pub a: GemmOperand,
+    /// BLAS 调用的右操作数，交换时为 `Aᵀ`。
+    pub b: GemmOperand,
+    /// BLAS 调用的输出，总是不转置，交换时为 `Cᵀ`。
+    pub c: GemmOperand,
+}
+
+impl<const N: usize> ArrayLayout<N> {
+    /// 将 2 维矩阵或 3 维批量矩阵的布局转换为 BLAS 操作数描述。
+    ///
+    /// 行主序和列主序的矩阵不需要拷贝，长度为 1 的维度的步长被忽略，
+    /// 批次可以是步长为 0 的广播。需要拷贝时返回 `None`。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, Endian::{BigEndian, LittleEndian}, GemmOperand};
+    /// let row_major = ArrayLayout::<3>::new_contiguous(&[4, 3, 5], BigEndian, 4);
+    /// assert_eq!(
+    ///     row_major.as_gemm_operand(4),
+    ///     Some(GemmOperand { batch: 4, rows: 3, cols: 5, trans: true, ld: 5, batch_stride: 15, offset: 0 })
+    /// );
+    ///
+    /// let col_major = ArrayLayout::<3>::new_contiguous(&[3, 5], LittleEndian, 4);
+    /// let operand = col_major.as_gemm_operand(4).unwrap();
+    /// assert_eq!((operand.trans, operand.ld), (false, 3));
+    ///
+    /// // 列方向有间隔的矩阵需要拷贝
+    /// assert_eq!(row_major.slice(2, 0, 2, 3).as_gemm_operand(4), None);
+    /// ```
+    pub fn as_gemm_operand(&self, element_size: usize) -> Option<GemmOperand> {
+        let (batch, batch_stride, rows, cols, rs, cs) = match (self.shape(), self.strides()) {
+            (&[rows, cols], &[rs, cs]) => (1, 0, rows, cols, rs, cs),
+            (&[batch, rows, cols], &[bs, rs, cs]) => (batch, bs, rows, cols, rs, cs),
+            _ => return None,
+        };
+
+        let es = element_size as isize;
+        let elements = |d: usize, s: isize| {
+            if d <= 1 {
+                Some(0)
+            } else if s >= 0 && s % es == 0 {
+                Some((s / es) as usize)
+            } else {
+                None
+            }
+        };
+        let batch_stride = elements(batch, batch_stride)? as isize;
+        let rs = elements(rows, rs)?;
+        let cs = elements(cols, cs)?;
+
+        // 连续维度的步长必须为 1，另一维的步长即主维度，不能小于连续维度的长度
+        let major = |inner: usize, inner_s: usize, outer: usize, outer_s: usize| {
+            if inner > 1 && inner_s != 1 {
+                return None;
+            }
+            let ld = if outer > 1 { outer_s } else { inner.max(1) };
+            (ld >= inner.max(1)).then_some(ld)
+        };
+        let (trans, ld) = match major(rows, rs, cols, cs) {
+            Some(ld) => (false, ld),
+            None => (true, major(cols, cs, rows, rs)?),
+        };
+
+        Some(GemmOperand {
+            batch,
+            rows,
+            cols,
+            trans,
+            ld,
+            batch_stride,
+            offset: self.offset(),
+        })
+    }
+
+    /// 为批量矩阵乘 `C = A · B` 选择 BLAS 调用方案。
+    ///
+    /// BLAS 的输出必须是列主序的。如果 `C` 不是列主序而 `Cᵀ` 是，
+    /// 则交换并转置操作数计算 `Cᵀ = Bᵀ · Aᵀ`，从而避免拷贝。
+    /// 任何操作数需要拷贝或形状不匹配时返回 `None`。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, Endian::BigEndian};
+    /// let c = ArrayLayout::<2>::new_contiguous(&[4, 5], BigEndian, 4);
+    /// let a = ArrayLayout::<2>::new_contiguous(&[4, 3], BigEndian, 4);
+    /// let b = ArrayLayout::<2>::new_contiguous(&[3, 5], BigEndian, 4);
+    ///
+    /// let plan = ArrayLayout::plan_gemm(&c, &a, &b, 4).unwrap();
+    /// assert!(plan.swap);
+    /// assert_eq!((plan.m, plan.n, plan.k), (5, 4, 3));
+    /// assert_eq!((plan.a.trans, plan.a.ld), (false, 5));
+    /// assert_eq!((plan.b.trans, plan.b.ld), (false, 3));
+    /// assert_eq!((plan.c.trans, plan.c.ld), (false, 5));
+    /// ```
+    pub fn plan_gemm(c: &Self, a: &Self, b: &Self, element_size: usize) -> Option<GemmPlan> {
+        let direct = |c: &Self, a: &Self, b: &Self, swap| {
+            let c = c.as_gemm_operand(element_size).filter(|c| !c.trans)?;
+            let a = a.as_gemm_operand(element_size)?;
+            let b = b.as_gemm_operand(element_size)?;
+
+            let (m, n, k) = (c.rows, c.cols, a.cols);
+            if a.rows != m || b.rows != k || b.cols != n {
+                return None;
+            }
+            // 输出的批次不能广播，输入的批次与输出相同或为 1，长度为 1 的批次步长为 0
+            if c.batch > 1 && c.batch_stride == 0 {
+                return None;
+            }
+            let batch = c.batch;
+            if ![a, b].iter().all(|x| x.batch == batch || x.batch == 1) {
+                return None;
+            }
+            Some(GemmPlan {
+                swap,
+                batch,
+                m,
+                n,
+                k,
+                a,
+                b,
+                c,
+            })
+        };
+        direct(c, a, b, false).or_else(|| {
+            let t = |x: &Self| {
+                let n = x.ndim;
+                if n < 2 {
+                    x.clone()
+                } else {
+                    x.transpose(&[n - 1, n - 2])
+                }
+            };
+            direct(&t(c), &t(b), &t(a), true)
+        })
+    }
+}
+
+#[test]
+fn test() {
+    use crate::Endian::BigEndian;
+
+    // 单行、单列和单元素矩阵
+    let row = ArrayLayout::<2>::new(&[1, 8], &[1000, 4], 8);
+    let operand = row.as_gemm_operand(4).unwrap();
+    assert_eq!((operand.trans, operand.ld), (false, 1));
+    let col = ArrayLayout::<2>::new(&[8, 1], &[4, 1000], 0);
+    assert_eq!(col.as_gemm_operand(4).unwrap().ld, 8);
+    let scalar = ArrayLayout::<2>::new(&[1, 1], &[7, 9], 0);
+    assert_eq!(scalar.as_gemm_operand(4).unwrap().ld, 1);
+
+    // 行步长为 1 但列步长小于行数，既不是列主序也不是行主序
+    let overlapped = ArrayLayout::<2>::new(&[4, 4], &[4, 8], 0);
+    assert_eq!(overlapped.as_gemm_operand(4), None);
+    // 负步长和未对齐的步长
+    let flipped = ArrayLayout::<2>::new_contiguous(&[4, 4], BigEndian, 4).slice(0, 3, -1, 4);
+    assert_eq!(flipped.as_gemm_operand(4), None);
+    assert_eq!(
+        ArrayLayout::<2>::new(&[2, 2], &[6, 3], 0).as_gemm_operand(4),
+        None
+    );
+    assert_eq!(
+        ArrayLayout::<3>::new(&[2], &[4], 0).as_gemm_operand(4),
+        None
+    );
+
+    // 广播批次的权重
+    let c = ArrayLayout::<3>::new_contiguous(&[4, 5, 6], crate::Endian::LittleEndian, 4)
+        .transpose(&[2, 0, 1]);
+    let a = ArrayLayout::<3>::new_contiguous(&[6, 3, 4], BigEndian, 4).transpose(&[0, 2, 1]);
+    let b = ArrayLayout::<3>::new_contiguous(&[1, 3, 5], BigEndian, 4).broadcast(0, 6);
+    assert_eq!(c.shape(), &[6, 4, 5]);
+    let plan = ArrayLayout::plan_gemm(&c, &a, &b, 4).unwrap();
+    assert!(!plan.swap);
+    assert_eq!((plan.batch, plan.m, plan.n, plan.k), (6, 4, 5, 3));
+    assert_eq!(
+        (plan.a.trans, plan.a.ld, plan.a.batch_stride),
+        (false, 4, 12)
+    );
+    assert_eq!((plan.b.trans, plan.b.ld, plan.b.batch_stride), (true, 5, 0));
+    assert_eq!((plan.c.ld, plan.c.batch_stride), (4, 20));
+
+    // 形状不匹配
+    assert!(ArrayLayout::plan_gemm(&c, &b, &a, 4).is_none());
+    // 批次长度为 1 的输入可以广播，长度不同的广播批次不行
+    let b1 = ArrayLayout::<3>::new_contiguous(&[1, 3, 5], BigEndian, 4);
+    let plan = ArrayLayout::plan_gemm(&c, &a, &b1, 4).unwrap();
+    assert_eq!((plan.batch, plan.b.batch, plan.b.batch_stride), (6, 1, 0));
+    let b3 = b1.broadcast(0, 3);
+    assert!(ArrayLayout::plan_gemm(&c, &a, &b3, 4).is_none());
+}
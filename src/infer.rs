@@ -0,0 +1,330 @@
+use crate::{ArrayLayout, BroadcastArg, Endian, IndexArg, SliceArg};
+use std::{error::Error, fmt, iter::zip};
+
+/// 形状推导的错误。
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ShapeError {
+    /// 轴超出维数。
+    AxisOutOfRange {
+        /// 出错的轴。
+        axis: usize,
+        /// 维数。
+        ndim: usize,
+    },
+    /// 轴重复或未按升序排列。
+    UnorderedAxes {
+        /// 出错的轴。
+        axis: usize,
+    },
+    /// 维数不足。
+    RankTooLow {
+        /// 维数。
+        ndim: usize,
+        /// 要求的最小维数。
+        min: usize,
+    },
+    /// 矩阵乘的收缩维度长度不一致。
+    ContractionMismatch {
+        /// 左操作数的收缩维度长度。
+        lhs: usize,
+        /// 右操作数的收缩维度长度。
+        rhs: usize,
+    },
+    /// 批次维度无法广播。
+    BroadcastMismatch {
+        /// 输出中的批次轴。
+        axis: usize,
+        /// 左操作数的长度。
+        lhs: usize,
+        /// 右操作数的长度。
+        rhs: usize,
+    },
+}
+
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AxisOutOfRange { axis, ndim } => write!(f, "axis {axis} out of {ndim} dims"),
+            Self::UnorderedAxes { axis } => write!(f, "axis {axis} is duplicated or unordered"),
+            Self::RankTooLow { ndim, min } => write!(f, "{ndim} dims given, {min} required"),
+            Self::ContractionMismatch { lhs, rhs } => {
+                write!(f, "contraction dims mismatch: {lhs} vs {rhs}")
+            }
+            Self::BroadcastMismatch { axis, lhs, rhs } => {
+                write!(f, "cannot broadcast batch axis {axis}: {lhs} vs {rhs}")
+            }
+        }
+    }
+}
+
+impl Error for ShapeError {}
+
+/// 矩阵乘的形状推导结果。
+#[derive(Clone, PartialEq, Eq)]
+pub struct MatMul<const N: usize> {
+    /// 输出形状，与 NumPy 的 `matmul` 一致。
+    pub shape: Vec<usize>,
+    /// 广播批次维度后形状为 `[...batch, m, k]` 的左操作数。
+    pub a: ArrayLayout<N>,
+    /// 广播批次维度后形状为 `[...batch, k, n]` 的右操作数。
+    pub b: ArrayLayout<N>,
+}
+
+impl<const N: usize> MatMul<N> {
+    /// 创建输出的连续布局。
+    #[inline]
+    pub fn output(&self, endian: Endian, element_size: usize) -> ArrayLayout<N> {
+        ArrayLayout::new_contiguous(&self.shape, endian, element_size)
+    }
+}
+
+/// 规约的形状推导结果。
+#[derive(Clone, PartialEq, Eq)]
+pub struct Reduce<const N: usize> {
+    /// 输出形状，`keepdims` 时规约的轴保留为长度 1。
+    pub shape: Vec<usize>,
+    /// 遍历输出位置的输入视图，每个元素指向对应规约区域的起点。
+    pub outer: ArrayLayout<N>,
+    /// 规约区域的子布局，偏移为 0，用于内层循环。
+    pub inner: ArrayLayout<N>,
+}
+
+impl<const N: usize> Reduce<N> {
+    /// 创建输出的连续布局。
+    #[inline]
+    pub fn output(&self, endian: Endian, element_size: usize) -> ArrayLayout<N> {
+        ArrayLayout::new_contiguous(&self.shape, endian, element_size)
+    }
+}
+
+impl<const N: usize> ArrayLayout<N> {
+    /// 推导矩阵乘 `a · b` 的形状，批次维度按 NumPy 的规则广播。
+    ///
+    /// 1 维的 `a` 视作行向量，1 维的 `b` 视作列向量，对应的维度不出现在输出中。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, Endian::BigEndian};
+    /// let a = ArrayLayout::<4>::new_contiguous(&[2, 1, 3, 4], BigEndian, 4);
+    /// let b = ArrayLayout::<4>::new_contiguous(&[5, 4, 6], BigEndian, 4);
+    ///
+    /// let matmul = ArrayLayout::matmul_shape(&a, &b).unwrap();
+    /// assert_eq!(matmul.shape, &[2, 5, 3, 6]);
+    /// assert_eq!(matmul.a.shape(), &[2, 5, 3, 4]);
+    /// assert_eq!(matmul.a.strides(), &[48, 0, 16, 4]);
+    /// assert_eq!(matmul.b.shape(), &[2, 5, 4, 6]);
+    /// assert_eq!(matmul.b.strides(), &[0, 96, 24, 4]);
+    ///
+    /// let v = ArrayLayout::<4>::new_contiguous(&[6], BigEndian, 4);
+    /// assert_eq!(ArrayLayout::matmul_shape(&b, &v).unwrap().shape, &[5, 4]);
+    /// assert!(ArrayLayout::matmul_shape(&a, &v).is_err());
+    /// ```
+    pub fn matmul_shape(a: &Self, b: &Self) -> Result<MatMul<N>, ShapeError> {
+        let a_vec = a.ndim == 1;
+        let b_vec = b.ndim == 1;
+        for x in [a, b] {
+            if x.ndim == 0 {
+                return Err(ShapeError::RankTooLow { ndim: 0, min: 1 });
+            }
+        }
+        let a_ = if a_vec {
+            a.tile_be(0, &[1, a.shape()[0]])
+        } else {
+            a.clone()
+        };
+        let b_ = if b_vec {
+            b.tile_be(0, &[b.shape()[0], 1])
+        } else {
+            b.clone()
+        };
+
+        let (k_a, k_b) = (a_.shape()[a_.ndim - 1], b_.shape()[b_.ndim - 2]);
+        if k_a != k_b {
+            return Err(ShapeError::ContractionMismatch { lhs: k_a, rhs: k_b });
+        }
+
+        // 右对齐广播批次维度
+        let rank = a_.ndim.max(b_.ndim);
+        let a_ = a_.unsqueeze_front(rank - a_.ndim);
+        let b_ = b_.unsqueeze_front(rank - b_.ndim);
+        let mut batch = Vec::with_capacity(rank - 2);
+        for (axis, (&da, &db)) in zip(&a_.shape()[..rank - 2], &b_.shape()[..rank - 2]).enumerate()
+        {
+            batch.push(match (da, db) {
+                (1, d) | (d, 1) => d,
+                (da, db) if da == db => da,
+                (lhs, rhs) => return Err(ShapeError::BroadcastMismatch { axis, lhs, rhs }),
+            })
+        }
+        let args = |x: &Self| {
+            zip(x.shape(), &batch)
+                .enumerate()
+                .filter(|&(_, (&d, &times))| d != times)
+                .map(|(axis, (_, &times))| BroadcastArg { axis, times })
+                .collect::<Vec<_>>()
+        };
+        let a_ = a_.broadcast_many(&args(&a_));
+        let b_ = b_.broadcast_many(&args(&b_));
+
+        let mut shape = batch;
+        if !a_vec {
+            shape.push(a_.shape()[rank - 2])
+        }
+        if !b_vec {
+            shape.push(b_.shape()[rank - 1])
+        }
+        Ok(MatMul {
+            shape,
+            a: a_,
+            b: b_,
+        })
+    }
+
+    /// 推导沿 `axes` 规约的形状，`axes` 必须升序排列且不重复。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, Endian::BigEndian};
+    /// let layout = ArrayLayout::<3>::new_contiguous(&[2, 3, 4], BigEndian, 4);
+    ///
+    /// let reduce = layout.reduce(&[0, 2], false).unwrap();
+    /// assert_eq!(reduce.shape, &[3]);
+    /// assert_eq!(reduce.outer.shape(), &[3]);
+    /// assert_eq!(reduce.outer.strides(), &[16]);
+    /// assert_eq!(reduce.inner.shape(), &[2, 4]);
+    /// assert_eq!(reduce.inner.strides(), &[48, 4]);
+    ///
+    /// let reduce = layout.slice(1, 1, 1, 2).reduce(&[2], true).unwrap();
+    /// assert_eq!(reduce.shape, &[2, 2, 1]);
+    /// assert_eq!(reduce.outer.offset(), 16);
+    /// assert_eq!(reduce.inner.offset(), 0);
+    /// ```
+    pub fn reduce(&self, axes: &[usize], keepdims: bool) -> Result<Reduce<N>, ShapeError> {
+        let ndim = self.ndim;
+        for (i, &axis) in axes.iter().enumerate() {
+            if axis >= ndim {
+                return Err(ShapeError::AxisOutOfRange { axis, ndim });
+            }
+            if i > 0 && axes[i - 1] >= axis {
+                return Err(ShapeError::UnorderedAxes { axis });
+            }
+        }
+
+        let reduced = |i| axes.binary_search(&i).is_ok();
+        let shape = (0..ndim)
+            .filter_map(|i| match (reduced(i), keepdims) {
+                (false, _) => Some(self.shape()[i]),
+                (true, true) => Some(1),
+                (true, false) => None,
+            })
+            .collect();
+
+        let (outer, inner) = if self.num_elements() > 0 {
+            let index = |keep: bool| {
+                (0..ndim)
+                    .filter(|&i| reduced(i) != keep)
+                    .map(|axis| IndexArg { axis, index: 0 })
+                    .collect::<Vec<_>>()
+            };
+            let outer = if keepdims {
+                let args = axes
+                    .iter()
+                    .map(|&axis| SliceArg {
+                        axis,
+                        start: 0,
+                        step: 1,
+                        len: 1,
+                    })
+                    .collect::<Vec<_>>();
+                self.slice_many(&args)
+            } else {
+                self.index_many(&index(false))
+            };
+            let inner = self.index_many(&index(true));
+            (outer, Self::new(inner.shape(), inner.strides(), 0))
+        } else {
+            // 空数组无法索引，直接按轴筛选
+            let select = |keep: &dyn Fn(usize) -> bool, offset| {
+                let (shape, strides): (Vec<_>, Vec<_>) = (0..ndim)
+                    .filter(|&i| keep(i))
+                    .map(|i| (self.shape()[i], self.strides()[i]))
+                    .unzip();
+                Self::new(&shape, &strides, offset)
+            };
+            let outer = select(&|i| keepdims || !reduced(i), self.offset());
+            let inner = select(&reduced, 0);
+            (outer, inner)
+        };
+
+        Ok(Reduce {
+            shape,
+            outer,
+            inner,
+        })
+    }
+
+    /// 在形状前添加 `n` 个长度为 1 的维度。
+    fn unsqueeze_front(&self, n: usize) -> Self {
+        let mut ans = self.clone();
+        for _ in 0..n {
+            ans = ans.tile_be(0, &[1, ans.shape()[0]])
+        }
+        ans
+    }
+}
+
+#[test]
+fn test() {
+    use Endian::BigEndian;
+
+    let a = ArrayLayout::<3>::new_contiguous(&[3, 4], BigEndian, 2);
+    let b = ArrayLayout::<3>::new_contiguous(&[7, 4, 5], BigEndian, 2);
+    let matmul = ArrayLayout::matmul_shape(&a, &b).unwrap();
+    assert_eq!(matmul.shape, &[7, 3, 5]);
+    assert_eq!(matmul.a.strides(), &[0, 8, 2]);
+    assert!(matmul.output(BigEndian, 2) == ArrayLayout::new_contiguous(&[7, 3, 5], BigEndian, 2));
+
+    // 向量点积
+    let v = ArrayLayout::<3>::new_contiguous(&[4], BigEndian, 2);
+    let dot = ArrayLayout::matmul_shape(&v, &v).unwrap();
+    assert!(dot.shape.is_empty());
+    assert_eq!(dot.a.shape(), &[1, 4]);
+    assert_eq!(dot.b.shape(), &[4, 1]);
+    assert_eq!(ArrayLayout::matmul_shape(&v, &b).unwrap().shape, &[7, 5]);
+
+    let c = ArrayLayout::<3>::new_contiguous(&[2, 3, 4], BigEndian, 2);
+    assert_eq!(
+        ArrayLayout::matmul_shape(&c, &b).err(),
+        Some(ShapeError::BroadcastMismatch {
+            axis: 0,
+            lhs: 2,
+            rhs: 7
+        })
+    );
+    let scalar = ArrayLayout::<3>::new(&[], &[], 0);
+    assert_eq!(
+        ArrayLayout::matmul_shape(&scalar, &v).err(),
+        Some(ShapeError::RankTooLow { ndim: 0, min: 1 })
+    );
+
+    // 规约的轴
+    assert_eq!(
+        c.reduce(&[3], false).err(),
+        Some(ShapeError::AxisOutOfRange { axis: 3, ndim: 3 })
+    );
+    assert_eq!(
+        c.reduce(&[1, 1], false).err(),
+        Some(ShapeError::UnorderedAxes { axis: 1 })
+    );
+    let all = c.reduce(&[0, 1, 2], false).unwrap();
+    assert!(all.shape.is_empty());
+    assert!(all.inner == c);
+
+    // 空数组
+    let empty = ArrayLayout::<3>::new_contiguous(&[2, 0, 3], BigEndian, 2);
+    let reduce = empty.reduce(&[1], false).unwrap();
+    assert_eq!(reduce.shape, &[2, 3]);
+    assert_eq!(reduce.inner.shape(), &[0]);
+    assert_eq!(
+        ShapeError::RankTooLow { ndim: 0, min: 1 }.to_string(),
+        "0 dims given, 1 required"
+    );
+}
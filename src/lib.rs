@@ -100,6 +100,50 @@ impl<const N: usize> ArrayLayout<N> {
         ans
     }
 
+    /// Creates a new contiguous Layout with the given shape, rounding the stride of each axis up to the given alignment in bytes.
+    ///
+    /// An alignment of 0 or 1 leaves the stride of the axis unchanged.
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{Endian, ArrayLayout};
+    /// // each row of 5 f32 is padded to 64 bytes, like `cudaMallocPitch`
+    /// let layout = ArrayLayout::<4>::new_aligned(&[2, 3, 5], Endian::BigEndian, 4, &[0, 64, 0]);
+    /// assert_eq!(layout.shape(), &[2, 3, 5]);
+    /// assert_eq!(layout.strides(), &[192, 64, 4]);
+    /// assert_eq!(layout.row_pitch(), Some(64));
+    /// assert_eq!(layout.padding_bytes(4), 2 * 3 * 44 - 44);
+    /// assert!(!layout.is_contiguous(4));
+    /// assert!(layout.is_contiguous_padded(4));
+    /// ```
+    pub fn new_aligned(
+        shape: &[usize],
+        endian: Endian,
+        element_size: usize,
+        align_per_axis: &[usize],
+    ) -> Self {
+        assert_eq!(
+            shape.len(),
+            align_per_axis.len(),
+            "shape and alignments must have the same length"
+        );
+
+        let mut ans = Self::with_ndim(shape.len());
+        let mut content = ans.content_mut();
+        content.set_offset(0);
+        content.copy_shape(shape);
+        let mut mul = element_size;
+        let push = |i: usize| {
+            let stride = mul.next_multiple_of(align_per_axis[i].max(1));
+            content.set_stride(i, stride as _);
+            mul = stride * shape[i];
+        };
+        match endian {
+            Endian::BigEndian => (0..shape.len()).rev().for_each(push),
+            Endian::LittleEndian => (0..shape.len()).for_each(push),
+        }
+        ans
+    }
+
     /// Gets offset.
     #[inline]
     pub const fn ndim(&self) -> usize {
@@ -196,17 +240,152 @@ impl<const N: usize> ArrayLayout<N> {
         }
         start..=end
     }
+
+    /// Gets the row pitch, which is the second smallest absolute stride among dimensions longer than 1.
+    ///
+    /// Returns `None` if there are fewer than 2 such dimensions.
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{Endian::LittleEndian, ArrayLayout};
+    /// let layout = ArrayLayout::<4>::new_aligned(&[5, 3], LittleEndian, 2, &[0, 16]);
+    /// assert_eq!(layout.row_pitch(), Some(16));
+    /// assert_eq!(layout.index(1, 0).row_pitch(), None);
+    /// ```
+    pub fn row_pitch(&self) -> Option<usize> {
+        let mut strides = self.sorted_strides();
+        strides.nth(1).map(|(_, s)| s)
+    }
+
+    /// Calculates the number of bytes inside [`data_range`](Self::data_range) not covered by any element.
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{Endian::BigEndian, ArrayLayout};
+    /// let layout = ArrayLayout::<4>::new_contiguous(&[4, 6], BigEndian, 2);
+    /// assert_eq!(layout.padding_bytes(2), 0);
+    /// assert_eq!(layout.slice(1, 0, 1, 4).padding_bytes(2), 3 * 4);
+    /// ```
+    pub fn padding_bytes(&self, element_size: usize) -> usize {
+        let n = self.num_elements();
+        if n == 0 {
+            return 0;
+        }
+        let range = self.data_range();
+        let span = (range.end() - range.start()) as usize + element_size;
+        span.saturating_sub(n * element_size)
+    }
+
+    /// Checks whether the elements of the array fill a gapless memory area, in any order of dimensions.
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{Endian::BigEndian, ArrayLayout};
+    /// let layout = ArrayLayout::<4>::new_contiguous(&[2, 3, 4], BigEndian, 4);
+    /// assert!(layout.is_contiguous(4));
+    /// assert!(layout.transpose(&[2, 0, 1]).slice(1, 1, -1, 2).is_contiguous(4));
+    /// assert!(!layout.slice(2, 0, 1, 3).is_contiguous(4));
+    /// ```
+    pub fn is_contiguous(&self, element_size: usize) -> bool {
+        self.is_nested(element_size, |inner, outer| inner == outer)
+    }
+
+    /// Checks whether the array is contiguous except for padding between rows and other outer dimensions.
+    ///
+    /// Every absolute stride, sorted ascending, must be at least the span of the smaller ones,
+    /// and the smallest must equal `element_size`.
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{Endian::BigEndian, ArrayLayout};
+    /// let layout = ArrayLayout::<4>::new_contiguous(&[2, 3, 4], BigEndian, 4);
+    /// assert!(layout.slice(2, 0, 1, 3).is_contiguous_padded(4));
+    /// assert!(!layout.slice(2, 0, 2, 2).is_contiguous_padded(4));
+    /// ```
+    pub fn is_contiguous_padded(&self, element_size: usize) -> bool {
+        self.is_nested(element_size, |inner, outer| inner <= outer)
+    }
+
+    /// Checks whether different elements of the array share the same offset.
+    ///
+    /// Broadcast dimensions and overlapping windows make an array self-overlapping.
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{Endian::BigEndian, ArrayLayout};
+    /// let layout = ArrayLayout::<4>::new_contiguous(&[2, 3, 4], BigEndian, 4);
+    /// assert!(!layout.has_overlap());
+    /// assert!(layout.slice(2, 0, 1, 1).broadcast(2, 4).has_overlap());
+    /// assert!(ArrayLayout::<4>::new(&[4, 3], &[2, 3], 0).has_overlap()); // 3 * 2 == 2 * 3
+    /// assert!(!ArrayLayout::<4>::new(&[3, 3], &[2, 3], 0).has_overlap());
+    /// ```
+    pub fn has_overlap(&self) -> bool {
+        if self.shape().contains(&0) {
+            return false;
+        }
+
+        let dims = self.sorted_strides().collect::<Vec<_>>();
+        // no overlap if every stride exceeds the span of all smaller dimensions
+        let mut span = 0;
+        if dims.iter().all(|&(d, s)| {
+            let ans = s > span;
+            span += s * (d - 1);
+            ans
+        }) {
+            return false;
+        }
+        if dims.first().is_some_and(|&(_, s)| s == 0) {
+            return true;
+        }
+
+        let mut offsets = (0..self.num_elements())
+            .map(|i| self.element_offset(i, Endian::BigEndian))
+            .collect::<Vec<_>>();
+        offsets.sort_unstable();
+        offsets.windows(2).any(|w| w[0] == w[1])
+    }
 }
 
+mod analysis;
+mod blocked;
+mod builder;
+mod codegen;
+mod conv;
+mod derive;
+mod elementwise;
 mod fmt;
+mod gemm;
+mod infer;
+mod named;
+mod padded;
+mod partition;
+mod shard;
+mod symbolic;
+mod traced;
 mod transform;
-pub use transform::{BroadcastArg, IndexArg, MergeArg, SliceArg, Split, TileArg};
+mod view;
+mod viz;
+pub use analysis::{AccessReport, Footprint, Order, WarpAccess, WarpConfig};
+pub use blocked::Blocking;
+pub use builder::LayoutBuilder;
+pub use codegen::Lang;
+pub use conv::{DataFormat, Im2col};
+pub use elementwise::ElementwisePlan;
+pub use fmt::{Bf16, Bool, F16, FormatElement, Hex, PrintOptions, Printed, Style};
+pub use gemm::{GemmOperand, GemmPlan};
+pub use infer::{MatMul, Reduce, ShapeError};
+pub use named::{NameError, NamedLayout};
+pub use padded::{PadMode, PaddedLayout};
+pub use partition::Part;
+pub use shard::{Collective, Placement, ShardSpec};
+pub use symbolic::{BindError, Constraint, Expr, SymbolicLayout};
+pub use traced::TracedLayout;
+pub use transform::{
+    BroadcastArg, IndexArg, MergeArg, ParseTransformError, SliceArg, Split, TileArg, Transform,
+    WindowArg,
+};
+pub use view::{TensorView, TensorViewMut, ViewError};
 
 use std::{
-    alloc::{Layout, alloc, dealloc},
+    alloc::{Layout, alloc, dealloc, handle_alloc_error, realloc},
     iter::zip,
-    ops::RangeInclusive,
-    ptr::{NonNull, copy_nonoverlapping},
+    ops::{Range, RangeInclusive},
+    ptr::{NonNull, copy, copy_nonoverlapping},
     slice::from_raw_parts,
 };
 
@@ -241,6 +420,31 @@ impl<const N: usize> ArrayLayout<N> {
         }
     }
 
+    /// Iterates dimensions longer than 1 as `(len, |stride|)`, sorted by absolute stride.
+    fn sorted_strides(&self) -> std::vec::IntoIter<(usize, usize)> {
+        let mut dims = zip(self.shape(), self.strides())
+            .filter(|&(&d, _)| d > 1)
+            .map(|(&d, &s)| (d, s.unsigned_abs()))
+            .collect::<Vec<_>>();
+        dims.sort_unstable_by_key(|&(_, s)| s);
+        dims.into_iter()
+    }
+
+    /// Checks the span of each dimension against the stride of the next larger one.
+    fn is_nested(&self, element_size: usize, check: impl Fn(usize, usize) -> bool) -> bool {
+        let mut dims = self.sorted_strides();
+        let mut span = match dims.next() {
+            Some((d, s)) if s == element_size => s * d,
+            Some(_) => return false,
+            None => return true,
+        };
+        dims.all(|(d, s)| {
+            let ans = check(span, s);
+            span = s * d;
+            ans
+        })
+    }
+
     /// Create a new ArrayLayout with the given dimensions.
     #[inline]
     fn with_ndim(ndim: usize) -> Self {
@@ -257,6 +461,84 @@ impl<const N: usize> ArrayLayout<N> {
             },
         }
     }
+
+    /// Changes the number of dimensions in place, keeping the offset and the leading dimensions.
+    ///
+    /// The inline storage is reused while `ndim` fits, and the heap storage is reallocated while it doesn't.
+    /// Dimensions added at the end are left uninitialized for the caller to set.
+    fn set_ndim(&mut self, ndim: usize) {
+        let old = self.ndim;
+        if ndim == old {
+            return;
+        }
+        let keep = old.min(ndim);
+        let inlined = NonNull::from(&mut self.content).cast::<usize>();
+        // moves the kept strides behind the shape of the new length
+        let move_strides = |ptr: NonNull<usize>| unsafe {
+            copy(ptr.add(1 + old).as_ptr(), ptr.add(1 + ndim).as_ptr(), keep)
+        };
+        unsafe {
+            match (old > N, ndim > N) {
+                (false, false) => move_strides(inlined),
+                (true, true) => {
+                    let mut ptr = self.content.ptr;
+                    if ndim < old {
+                        move_strides(ptr)
+                    }
+                    let new = layout(ndim);
+                    ptr = NonNull::new(realloc(ptr.cast().as_ptr(), layout(old), new.size()))
+                        .unwrap_or_else(|| handle_alloc_error(new))
+                        .cast();
+                    if ndim > old {
+                        move_strides(ptr)
+                    }
+                    self.content.ptr = ptr
+                }
+                (false, true) => {
+                    let ptr = NonNull::new(alloc(layout(ndim)))
+                        .unwrap_or_else(|| handle_alloc_error(layout(ndim)))
+                        .cast::<usize>();
+                    copy_nonoverlapping(inlined.as_ptr(), ptr.as_ptr(), 1 + keep);
+                    copy_nonoverlapping(
+                        inlined.add(1 + old).as_ptr(),
+                        ptr.add(1 + ndim).as_ptr(),
+                        keep,
+                    );
+                    self.content.ptr = ptr
+                }
+                (true, false) => {
+                    let ptr = self.content.ptr;
+                    copy_nonoverlapping(ptr.as_ptr(), inlined.as_ptr(), 1 + keep);
+                    copy_nonoverlapping(
+                        ptr.add(1 + old).as_ptr(),
+                        inlined.add(1 + ndim).as_ptr(),
+                        keep,
+                    );
+                    dealloc(ptr.cast().as_ptr(), layout(old))
+                }
+            }
+        }
+        self.ndim = ndim
+    }
+
+    /// Replaces the dimensions in `range` with `len` uninitialized ones, resizing the storage once.
+    fn splice_dims(&mut self, range: Range<usize>, len: usize) {
+        let ndim = self.ndim;
+        let dst = range.start + len;
+        if len < range.len() {
+            let mut content = self.content_mut();
+            for (i, j) in zip(dst.., range.end..ndim) {
+                content.copy_dim(j, i)
+            }
+            self.set_ndim(ndim - range.len() + len)
+        } else {
+            self.set_ndim(ndim - range.len() + len);
+            let mut content = self.content_mut();
+            for j in (range.end..ndim).rev() {
+                content.copy_dim(j, j - range.end + dst)
+            }
+        }
+    }
 }
 
 struct Content<const MUT: bool> {
@@ -304,6 +586,12 @@ impl Content<true> {
         unsafe { self.ptr.add(1 + idx + self.ndim).cast().write(val) }
     }
 
+    #[inline]
+    fn copy_dim(&mut self, from: usize, to: usize) {
+        self.set_shape(to, self.shape()[from]);
+        self.set_stride(to, self.strides()[from]);
+    }
+
     #[inline]
     fn copy_shape(&mut self, val: &[usize]) {
         assert!(val.len() == self.ndim);
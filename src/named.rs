@@ -0,0 +1,329 @@
+use crate::ArrayLayout;
+use std::{error::Error, fmt};
+
+/// 命名轴操作的错误。
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum NameError {
+    /// 轴名不存在。
+    Unknown(String),
+    /// 轴名重复。
+    Duplicate(String),
+    /// 名字数量与维数不一致。
+    RankMismatch {
+        /// 维数。
+        ndim: usize,
+        /// 名字数量。
+        names: usize,
+    },
+    /// 要合并的轴不相邻或顺序不对。
+    NotAdjacent(String),
+    /// 要合并的轴的步长无法合并。
+    NotMergeable(String),
+    /// 分块之积与原维度长度不一致。
+    TileMismatch {
+        /// 分块的轴。
+        name: String,
+        /// 原维度长度。
+        len: usize,
+        /// 分块之积。
+        product: usize,
+    },
+}
+
+impl fmt::Display for NameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown(name) => write!(f, "axis `{name}` not found"),
+            Self::Duplicate(name) => write!(f, "axis `{name}` is duplicated"),
+            Self::RankMismatch { ndim, names } => write!(f, "{names} names given for {ndim} dims"),
+            Self::NotAdjacent(name) => write!(f, "axis `{name}` is not adjacent to the previous"),
+            Self::NotMergeable(name) => write!(f, "axes cannot be merged into `{name}`"),
+            Self::TileMismatch { name, len, product } => {
+                write!(f, "cannot tile axis `{name}` of {len} into {product}")
+            }
+        }
+    }
+}
+
+impl Error for NameError {}
+
+/// 每个维度带有名字的布局。
+///
+/// 变换通过名字指定轴，名字在运行时检查。
+///
+/// ```rust
+/// # use ndarray_layout::{ArrayLayout, Endian::BigEndian, NamedLayout};
+/// let layout = ArrayLayout::<4>::new_contiguous(&[2, 5, 4, 8], BigEndian, 4);
+/// let layout = NamedLayout::new(layout, &["b", "s", "h", "d"])
+///     .unwrap()
+///     .transpose_to(&["b", "h", "s", "d"])
+///     .unwrap()
+///     .index_named("h", 3)
+///     .unwrap();
+/// assert_eq!(layout.names(), ["b", "s", "d"]);
+/// assert_eq!(layout.layout().shape(), &[2, 5, 8]);
+/// assert_eq!(layout.layout().strides(), &[640, 128, 4]);
+/// assert_eq!(layout.layout().offset(), 96);
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct NamedLayout<const N: usize> {
+    layout: ArrayLayout<N>,
+    names: Vec<String>,
+}
+
+impl<const N: usize> NamedLayout<N> {
+    /// 为布局的每个维度命名。
+    pub fn new(layout: ArrayLayout<N>, names: &[&str]) -> Result<Self, NameError> {
+        if names.len() != layout.ndim() {
+            return Err(NameError::RankMismatch {
+                ndim: layout.ndim(),
+                names: names.len(),
+            });
+        }
+        check_unique(names.iter().copied())?;
+        Ok(Self {
+            layout,
+            names: names.iter().map(|s| s.to_string()).collect(),
+        })
+    }
+
+    /// 内部的布局。
+    #[inline]
+    pub fn layout(&self) -> &ArrayLayout<N> {
+        &self.layout
+    }
+
+    /// 取出内部的布局。
+    #[inline]
+    pub fn into_layout(self) -> ArrayLayout<N> {
+        self.layout
+    }
+
+    /// 各维度的名字。
+    #[inline]
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// 名为 `name` 的轴的序号。
+    pub fn axis(&self, name: &str) -> Result<usize, NameError> {
+        self.names
+            .iter()
+            .position(|n| n == name)
+            .ok_or_else(|| NameError::Unknown(name.into()))
+    }
+
+    /// 按名字重排所有维度。
+    pub fn transpose_to(&self, names: &[&str]) -> Result<Self, NameError> {
+        if names.len() != self.names.len() {
+            return Err(NameError::RankMismatch {
+                ndim: self.names.len(),
+                names: names.len(),
+            });
+        }
+        check_unique(names.iter().copied())?;
+        let perm = names
+            .iter()
+            .map(|name| self.axis(name))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            layout: self.layout.transpose(&perm),
+            names: names.iter().map(|s| s.to_string()).collect(),
+        })
+    }
+
+    /// 在名为 `name` 的轴上索引，并移除这个轴。
+    pub fn index_named(&self, name: &str, index: usize) -> Result<Self, NameError> {
+        let axis = self.axis(name)?;
+        let mut names = self.names.clone();
+        names.remove(axis);
+        Ok(Self {
+            layout: self.layout.index(axis, index),
+            names,
+        })
+    }
+
+    /// 在名为 `name` 的轴上切片，语义同 [`ArrayLayout::slice`]。
+    pub fn slice_named(
+        &self,
+        name: &str,
+        start: usize,
+        step: isize,
+        len: usize,
+    ) -> Result<Self, NameError> {
+        let axis = self.axis(name)?;
+        Ok(Self {
+            layout: self.layout.slice(axis, start, step, len),
+            names: self.names.clone(),
+        })
+    }
+
+    /// 把按大端顺序相邻的若干轴合并为名为 `merged` 的轴。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, Endian::BigEndian, NamedLayout};
+    /// let layout = ArrayLayout::<4>::new_contiguous(&[2, 3, 4], BigEndian, 4);
+    /// let layout = NamedLayout::new(layout, &["s", "h", "d"]).unwrap();
+    /// let merged = layout.merge_named(&["h", "d"], "hd").unwrap();
+    /// assert_eq!(merged.names(), ["s", "hd"]);
+    /// assert_eq!(merged.layout().shape(), &[2, 12]);
+    ///
+    /// let tiled = merged.tile_named("hd", &["h", "d"], &[6, 2]).unwrap();
+    /// assert_eq!(tiled.names(), ["s", "h", "d"]);
+    /// assert_eq!(tiled.layout().shape(), &[2, 6, 2]);
+    /// assert_eq!(tiled.layout().strides(), &[48, 8, 4]);
+    /// ```
+    pub fn merge_named(&self, names: &[&str], merged: &str) -> Result<Self, NameError> {
+        let Some((first, tail)) = names.split_first() else {
+            return Err(NameError::RankMismatch {
+                ndim: self.names.len(),
+                names: 0,
+            });
+        };
+        let start = self.axis(first)?;
+        for (i, name) in tail.iter().enumerate() {
+            if self.axis(name)? != start + 1 + i {
+                return Err(NameError::NotAdjacent(name.to_string()));
+            }
+        }
+        let end = start + names.len();
+        let rest = self.names[..start].iter().chain(&self.names[end..]);
+        if rest.clone().any(|n| n == merged) {
+            return Err(NameError::Duplicate(merged.into()));
+        }
+
+        let layout = self
+            .layout
+            .merge_be(start, names.len())
+            .ok_or_else(|| NameError::NotMergeable(merged.into()))?;
+        let mut ans = self.names.clone();
+        ans.splice(start..end, [merged.to_string()]);
+        Ok(Self { layout, names: ans })
+    }
+
+    /// 把名为 `name` 的轴按大端顺序分块为若干命名的轴。
+    pub fn tile_named(
+        &self,
+        name: &str,
+        names: &[&str],
+        tiles: &[usize],
+    ) -> Result<Self, NameError> {
+        if names.len() != tiles.len() {
+            return Err(NameError::RankMismatch {
+                ndim: tiles.len(),
+                names: names.len(),
+            });
+        }
+        let axis = self.axis(name)?;
+        let len = self.layout.shape()[axis];
+        let product = tiles.iter().product::<usize>();
+        if len != product {
+            return Err(NameError::TileMismatch {
+                name: name.into(),
+                len,
+                product,
+            });
+        }
+        let rest = self.names[..axis].iter().chain(&self.names[axis + 1..]);
+        check_unique(names.iter().copied().chain(rest.map(String::as_str)))?;
+
+        let mut ans = self.names.clone();
+        ans.splice(axis..axis + 1, names.iter().map(|s| s.to_string()));
+        Ok(Self {
+            layout: self.layout.tile_be(axis, tiles),
+            names: ans,
+        })
+    }
+
+    /// 高维数组格式化，标题中带有轴名。
+    ///
+    /// # Safety
+    ///
+    /// 这个函数从对裸指针解引用以获得要格式化的数组元素。
+    pub unsafe fn write_array<T: fmt::Display + Copy>(
+        &self,
+        f: &mut fmt::Formatter,
+        ptr: *const T,
+    ) -> fmt::Result {
+        let dims = std::iter::zip(&self.names, self.layout.shape())
+            .map(|(n, d)| format!("{n}={d}"))
+            .collect::<Vec<_>>();
+        let title = format!("array<{}>", dims.join(", "));
+        unsafe { self.layout.write_titled(f, &title, ptr) }
+    }
+}
+
+fn check_unique<'a>(names: impl IntoIterator<Item = &'a str>) -> Result<(), NameError> {
+    let mut set = std::collections::HashSet::new();
+    for name in names {
+        if !set.insert(name) {
+            return Err(NameError::Duplicate(name.into()));
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test() {
+    use crate::Endian::BigEndian;
+
+    struct Tensor(NamedLayout<4>, Vec<u32>);
+    impl fmt::Display for Tensor {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            unsafe { self.0.write_array(f, self.1.as_ptr()) }
+        }
+    }
+
+    let layout = ArrayLayout::<4>::new_contiguous(&[2, 3], BigEndian, 4);
+    let layout = NamedLayout::new(layout, &["s", "d"]).unwrap();
+    let tensor = Tensor(layout.transpose_to(&["d", "s"]).unwrap(), (0..6).collect());
+    assert_eq!(
+        tensor.to_string(),
+        "\
+array<d=3, s=2>[..]
+0 3 \n\
+1 4 \n\
+2 5 \n"
+    );
+
+    assert_eq!(
+        NamedLayout::new(layout.layout().clone(), &["s"]).err(),
+        Some(NameError::RankMismatch { ndim: 2, names: 1 })
+    );
+    assert_eq!(
+        layout.transpose_to(&["s", "s"]).err(),
+        Some(NameError::Duplicate("s".into()))
+    );
+    assert_eq!(
+        layout.index_named("h", 0).err(),
+        Some(NameError::Unknown("h".into()))
+    );
+    assert_eq!(
+        layout.merge_named(&["d", "s"], "sd").err(),
+        Some(NameError::NotAdjacent("s".into()))
+    );
+    assert_eq!(
+        layout
+            .transpose_to(&["d", "s"])
+            .unwrap()
+            .merge_named(&["d", "s"], "ds")
+            .err(),
+        Some(NameError::NotMergeable("ds".into()))
+    );
+    assert_eq!(
+        layout.tile_named("d", &["a", "b"], &[2, 2]).err(),
+        Some(NameError::TileMismatch {
+            name: "d".into(),
+            len: 3,
+            product: 4
+        })
+    );
+    assert_eq!(
+        layout.tile_named("d", &["s", "b"], &[3, 1]).err(),
+        Some(NameError::Duplicate("s".into()))
+    );
+
+    let sliced = layout.slice_named("d", 2, -1, 3).unwrap();
+    assert_eq!(sliced.layout().strides(), &[12, -4]);
+    assert_eq!(sliced.layout().offset(), 8);
+}
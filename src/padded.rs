@@ -0,0 +1,260 @@
+use crate::{ArrayLayout, Endian, fmt::write_nd};
+use std::{fmt, iter::zip};
+
+/// 越界区域的填充方式。
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum PadMode {
+    /// 越界区域为常量。
+    Constant,
+    /// 以边缘为轴镜像，不重复边缘元素，例如 `[1, 2, 3]` 填充为 `[3, 2, 1, 2, 3, 2, 1]`。
+    Reflect,
+    /// 重复边缘元素，例如 `[1, 2, 3]` 填充为 `[1, 1, 1, 2, 3, 3, 3]`。
+    Replicate,
+    /// 循环填充，例如 `[1, 2, 3]` 填充为 `[2, 3, 1, 2, 3, 1, 2]`。
+    Circular,
+}
+
+/// 带有虚拟越界区域的布局。
+///
+/// 每个维度前后各填充若干元素，越界下标按填充方式映射回原布局，
+/// 或在常量填充时不对应任何元素。
+#[derive(Clone, PartialEq, Eq)]
+pub struct PaddedLayout<const N: usize> {
+    layout: ArrayLayout<N>,
+    pads: Vec<(usize, usize)>,
+    shape: Vec<usize>,
+    mode: PadMode,
+}
+
+impl<const N: usize> PaddedLayout<N> {
+    /// 为 `layout` 的每个维度添加 `(前, 后)` 填充。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, Endian::BigEndian, PadMode, PaddedLayout};
+    /// let layout = ArrayLayout::<2>::new_contiguous(&[2, 3], BigEndian, 4);
+    /// let padded = PaddedLayout::new(layout, &[(1, 1), (0, 2)], PadMode::Replicate);
+    /// assert_eq!(padded.shape(), &[4, 5]);
+    /// assert_eq!(padded.offset_of(&[0, 0]), Some(0));
+    /// assert_eq!(padded.offset_of(&[3, 4]), Some(20));
+    /// ```
+    pub fn new(layout: ArrayLayout<N>, pads: &[(usize, usize)], mode: PadMode) -> Self {
+        assert_eq!(
+            layout.ndim(),
+            pads.len(),
+            "layout and pads must have the same length"
+        );
+        if mode != PadMode::Constant {
+            for (&d, &(before, after)) in zip(layout.shape(), pads) {
+                assert!(
+                    d > 0 || before + after == 0,
+                    "cannot {mode:?} pad an empty dim"
+                );
+            }
+        }
+        let shape = zip(layout.shape(), pads)
+            .map(|(&d, &(before, after))| before + d + after)
+            .collect();
+        Self {
+            layout,
+            pads: pads.to_vec(),
+            shape,
+            mode,
+        }
+    }
+
+    /// 被填充的原布局。
+    #[inline]
+    pub fn layout(&self) -> &ArrayLayout<N> {
+        &self.layout
+    }
+
+    /// 每个维度的 `(前, 后)` 填充。
+    #[inline]
+    pub fn pads(&self) -> &[(usize, usize)] {
+        &self.pads
+    }
+
+    /// 填充方式。
+    #[inline]
+    pub fn mode(&self) -> PadMode {
+        self.mode
+    }
+
+    /// 维数。
+    #[inline]
+    pub fn ndim(&self) -> usize {
+        self.shape.len()
+    }
+
+    /// 填充后的形状。
+    #[inline]
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// 填充后的元素数量。
+    #[inline]
+    pub fn num_elements(&self) -> usize {
+        self.shape.iter().product()
+    }
+
+    /// 将填充后的下标映射为原布局中的下标，常量填充的越界区域返回 `None`。
+    pub fn source_index(&self, indices: &[usize]) -> Option<Vec<usize>> {
+        self.check(indices);
+        zip(indices, zip(self.layout.shape(), &self.pads))
+            .map(|(&i, (&d, &(before, _)))| self.map(i as isize - before as isize, d))
+            .collect()
+    }
+
+    /// 计算填充后下标 `indices` 处元素的偏移，常量填充的越界区域返回 `None`。
+    pub fn offset_of(&self, indices: &[usize]) -> Option<isize> {
+        self.check(indices);
+        let mut offset = self.layout.offset();
+        for ((&i, &s), (&d, &(before, _))) in zip(
+            zip(indices, self.layout.strides()),
+            zip(self.layout.shape(), &self.pads),
+        ) {
+            offset += self.map(i as isize - before as isize, d)? as isize * s
+        }
+        Some(offset)
+    }
+
+    /// 计算填充后按 `endian` 序第 `index` 个元素的偏移，
+    /// 常量填充的越界区域和超出填充后元素数量的序号返回 `None`。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, Endian::BigEndian, PadMode, PaddedLayout};
+    /// let layout = ArrayLayout::<1>::new_contiguous(&[3], BigEndian, 1);
+    /// let reflect = PaddedLayout::new(layout.clone(), &[(2, 2)], PadMode::Reflect);
+    /// let offsets = (0..7).map(|i| reflect.element_offset(i, BigEndian).unwrap());
+    /// assert_eq!(offsets.collect::<Vec<_>>(), [2, 1, 0, 1, 2, 1, 0]);
+    ///
+    /// let constant = PaddedLayout::new(layout, &[(1, 0)], PadMode::Constant);
+    /// assert_eq!(constant.element_offset(0, BigEndian), None);
+    /// assert_eq!(constant.element_offset(1, BigEndian), Some(0));
+    /// assert_eq!(constant.element_offset(4, BigEndian), None);
+    /// ```
+    pub fn element_offset(&self, index: usize, endian: Endian) -> Option<isize> {
+        if index >= self.num_elements() {
+            return None;
+        }
+        let mut indices = vec![0; self.ndim()];
+        let mut rem = index;
+        let mut push = |i: usize| {
+            let d = self.shape[i];
+            indices[i] = rem % d;
+            rem /= d
+        };
+        match endian {
+            Endian::BigEndian => (0..self.ndim()).rev().for_each(&mut push),
+            Endian::LittleEndian => (0..self.ndim()).for_each(&mut push),
+        }
+        self.offset_of(&indices)
+    }
+
+    /// 高维数组格式化，常量填充的越界区域显示为 `pad`。
+    ///
+    /// # Safety
+    ///
+    /// 这个函数从对裸指针解引用以获得要格式化的数组元素。
+    pub unsafe fn write_array<T: fmt::Display + Copy>(
+        &self,
+        f: &mut fmt::Formatter,
+        ptr: *const T,
+        pad: T,
+    ) -> fmt::Result {
+        let title = crate::fmt::title(&self.shape);
+        write_nd(f, &title, &self.shape, |indices| {
+            match self.offset_of(indices) {
+                Some(offset) => unsafe { ptr.byte_offset(offset).read_unaligned() },
+                None => pad,
+            }
+        })
+    }
+
+    /// 检查填充后的下标与阶数相同且不越界。
+    fn check(&self, indices: &[usize]) {
+        assert_eq!(indices.len(), self.ndim(), "index must match ndim");
+        for (axis, (&i, &d)) in zip(indices, &self.shape).enumerate() {
+            assert!(i < d, "index {i} out of {d} on axis {axis}")
+        }
+    }
+
+    /// 将相对于原布局起点的位置 `i` 映射到长度为 `d` 的维度中。
+    fn map(&self, i: isize, d: usize) -> Option<usize> {
+        let d = d as isize;
+        if (0..d).contains(&i) {
+            return Some(i as _);
+        }
+        let i = match self.mode {
+            PadMode::Constant => return None,
+            PadMode::Replicate => i.clamp(0, d - 1),
+            PadMode::Circular => i.rem_euclid(d),
+            PadMode::Reflect if d == 1 => 0,
+            PadMode::Reflect => {
+                let period = 2 * (d - 1);
+                let i = i.rem_euclid(period);
+                if i < d { i } else { period - i }
+            }
+        };
+        Some(i as _)
+    }
+}
+
+#[test]
+fn test() {
+    const DATA: [u8; 6] = [1, 2, 3, 4, 5, 6];
+
+    struct Tensor(PaddedLayout<2>);
+
+    impl fmt::Display for Tensor {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            unsafe { self.0.write_array(f, DATA.as_ptr(), 0) }
+        }
+    }
+
+    let layout = ArrayLayout::<2>::new_contiguous(&[2, 3], Endian::BigEndian, 1);
+    let row = |padded: &PaddedLayout<2>, r: usize| {
+        (0..padded.shape()[1])
+            .map(|c| padded.source_index(&[r, c]).map(|i| DATA[i[0] * 3 + i[1]]))
+            .collect::<Vec<_>>()
+    };
+
+    let padded = PaddedLayout::new(layout.clone(), &[(0, 0), (4, 4)], PadMode::Circular);
+    assert_eq!(
+        row(&padded, 1).into_iter().flatten().collect::<Vec<_>>(),
+        [6, 4, 5, 6, 4, 5, 6, 4, 5, 6, 4]
+    );
+    let padded = PaddedLayout::new(layout.clone(), &[(0, 0), (5, 5)], PadMode::Reflect);
+    assert_eq!(
+        row(&padded, 0).into_iter().flatten().collect::<Vec<_>>(),
+        [2, 1, 2, 3, 2, 1, 2, 3, 2, 1, 2, 3, 2]
+    );
+
+    // 填充后有长度为 0 的维度
+    let empty = ArrayLayout::<2>::new_contiguous(&[0, 3], Endian::BigEndian, 1);
+    let padded = PaddedLayout::new(empty, &[(0, 0), (1, 1)], PadMode::Constant);
+    assert_eq!(padded.shape(), &[0, 5]);
+    assert_eq!(padded.element_offset(0, Endian::BigEndian), None);
+
+    let padded = PaddedLayout::new(layout.clone(), &[(1, 1), (1, 1)], PadMode::Constant);
+    assert_eq!(padded.num_elements(), 20);
+    assert_eq!(padded.offset_of(&[1, 1]), Some(0));
+    assert_eq!(padded.offset_of(&[3, 2]), None);
+    assert_eq!(
+        Tensor(padded).to_string(),
+        "\
+array<4x5>[..]
+0 0 0 0 0 \n\
+0 1 2 3 0 \n\
+0 4 5 6 0 \n\
+0 0 0 0 0 \n"
+    );
+
+    // 负步长的原布局
+    let flipped = layout.slice(1, 2, -1, 3);
+    let padded = PaddedLayout::new(flipped, &[(0, 0), (1, 0)], PadMode::Replicate);
+    let offsets = (0..4).map(|c| padded.offset_of(&[1, c]).unwrap());
+    assert_eq!(offsets.collect::<Vec<_>>(), [5, 5, 4, 3]);
+    assert_eq!(padded.element_offset(4, Endian::BigEndian), Some(5));
+}
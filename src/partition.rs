@@ -0,0 +1,180 @@
+use crate::{ArrayLayout, Endian};
+
+/// 数组的一个分区，由若干矩形子布局组成。
+///
+/// 分区覆盖按指定端序排列的一段连续元素，子布局按元素顺序排列。
+#[derive(Clone, PartialEq, Eq)]
+pub struct Part<const N: usize> {
+    layouts: Vec<ArrayLayout<N>>,
+    endian: Endian,
+}
+
+impl<const N: usize> Part<N> {
+    /// 组成分区的矩形子布局，每个子布局与原布局维数相同。
+    #[inline]
+    pub fn layouts(&self) -> &[ArrayLayout<N>] {
+        &self.layouts
+    }
+
+    /// 取出组成分区的矩形子布局。
+    #[inline]
+    pub fn into_layouts(self) -> Vec<ArrayLayout<N>> {
+        self.layouts
+    }
+
+    /// 分区中的元素数量。
+    #[inline]
+    pub fn num_elements(&self) -> usize {
+        self.layouts.iter().map(ArrayLayout::num_elements).sum()
+    }
+
+    /// 按端序依次产生分区中每个元素的偏移。
+    pub fn offsets(&self) -> impl Iterator<Item = isize> + '_ {
+        let endian = self.endian;
+        self.layouts
+            .iter()
+            .flat_map(move |l| (0..l.num_elements()).map(move |i| l.element_offset(i, endian)))
+    }
+}
+
+impl<const N: usize> ArrayLayout<N> {
+    /// 将数组按 `endian` 序划分为至多 `parts` 个元素数量尽量相等的分区。
+    ///
+    /// 每个分区是一段连续的元素，跨越维度边界时表示为多个矩形子布局。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, Endian::BigEndian};
+    /// let layout = ArrayLayout::<3>::new_contiguous(&[3, 4], BigEndian, 1);
+    /// let parts = layout.partition(2, BigEndian);
+    /// assert_eq!(parts.len(), 2);
+    ///
+    /// // 前 6 个元素：第 0 行和第 1 行的前 2 个元素
+    /// let layouts = parts[0].layouts();
+    /// assert_eq!(layouts.len(), 2);
+    /// assert_eq!(layouts[0].shape(), &[1, 4]);
+    /// assert_eq!(layouts[1].shape(), &[1, 2]);
+    /// assert_eq!(layouts[1].offset(), 4);
+    /// assert_eq!(parts[1].offsets().collect::<Vec<_>>(), [6, 7, 8, 9, 10, 11]);
+    /// ```
+    pub fn partition(&self, parts: usize, endian: Endian) -> Vec<Part<N>> {
+        assert!(parts > 0, "parts must be positive");
+
+        let axes = match endian {
+            Endian::BigEndian => (0..self.ndim).collect::<Vec<_>>(),
+            Endian::LittleEndian => (0..self.ndim).rev().collect(),
+        };
+
+        let n = self.num_elements();
+        let (q, r) = (n / parts, n % parts);
+        let mut start = 0;
+        (0..parts)
+            .map(|i| q + usize::from(i < r))
+            .take_while(|&len| len > 0)
+            .map(|len| {
+                let mut layouts = Vec::new();
+                self.rectangles(&axes, start, start + len, &mut layouts);
+                start += len;
+                Part { layouts, endian }
+            })
+            .collect()
+    }
+
+    /// 将 `axes[0]` 及其内层维度构成的元素序号区间 `[start, end)` 分解为矩形子布局。
+    fn rectangles(&self, axes: &[usize], start: usize, end: usize, ans: &mut Vec<Self>) {
+        let [axis, inner_axes @ ..] = axes else {
+            if start < end {
+                ans.push(self.clone())
+            }
+            return;
+        };
+        if start >= end {
+            return;
+        }
+
+        let axis = *axis;
+        let inner = inner_axes
+            .iter()
+            .map(|&i| self.shape()[i])
+            .product::<usize>();
+        let (mut head, head_rem) = (start / inner, start % inner);
+        let (tail, tail_rem) = (end / inner, end % inner);
+
+        if head == tail {
+            return self
+                .slice(axis, head, 1, 1)
+                .rectangles(inner_axes, head_rem, tail_rem, ans);
+        }
+        if head_rem > 0 {
+            self.slice(axis, head, 1, 1)
+                .rectangles(inner_axes, head_rem, inner, ans);
+            head += 1
+        }
+        if tail > head {
+            ans.push(self.slice(axis, head, 1, tail - head))
+        }
+        if tail_rem > 0 {
+            self.slice(axis, tail, 1, 1)
+                .rectangles(inner_axes, 0, tail_rem, ans)
+        }
+    }
+}
+
+#[cfg(feature = "rayon")]
+mod par {
+    use crate::{ArrayLayout, Endian};
+    use rayon::prelude::*;
+
+    impl<const N: usize> ArrayLayout<N> {
+        /// 按 `endian` 序划分数组并并行产生每个元素的偏移。
+        ///
+        /// 数组被划分为与 rayon 线程数相同数量的分区，每个分区内的偏移按端序产生。
+        pub fn par_offsets(&self, endian: Endian) -> impl ParallelIterator<Item = isize> {
+            self.partition(rayon::current_num_threads(), endian)
+                .into_par_iter()
+                .flat_map_iter(|part| part.into_layouts())
+                .flat_map_iter(move |l| {
+                    (0..l.num_elements()).map(move |i| l.element_offset(i, endian))
+                })
+        }
+    }
+}
+
+#[test]
+fn test() {
+    let layout = ArrayLayout::<3>::new_contiguous(&[3, 4, 5], Endian::LittleEndian, 2);
+    for endian in [Endian::BigEndian, Endian::LittleEndian] {
+        let expected = (0..60)
+            .map(|i| layout.element_offset(i, endian))
+            .collect::<Vec<_>>();
+        for n in [1, 2, 7, 13, 60, 100] {
+            let parts = layout.partition(n, endian);
+            assert_eq!(parts.len(), n.min(60));
+
+            let sizes = parts.iter().map(Part::num_elements).collect::<Vec<_>>();
+            let (min, max) = (sizes.iter().min().unwrap(), sizes.iter().max().unwrap());
+            assert!(max - min <= 1);
+            // 跨越维度边界的分区最多由 2 * (ndim - 1) + 1 个矩形组成
+            assert!(parts.iter().all(|p| p.layouts().len() <= 5));
+            assert!(parts.iter().flat_map(Part::layouts).all(|l| l.ndim() == 3));
+
+            let actual = parts.iter().flat_map(Part::offsets).collect::<Vec<_>>();
+            assert_eq!(actual, expected);
+        }
+    }
+
+    let empty = ArrayLayout::<2>::new_contiguous(&[0, 4], Endian::BigEndian, 1);
+    assert!(empty.partition(4, Endian::BigEndian).is_empty());
+
+    let scalar = ArrayLayout::<2>::new(&[], &[], 3);
+    let parts = scalar.partition(4, Endian::BigEndian);
+    assert_eq!(parts.len(), 1);
+    assert_eq!(parts[0].offsets().collect::<Vec<_>>(), [3]);
+
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        let mut offsets = layout.par_offsets(Endian::BigEndian).collect::<Vec<_>>();
+        offsets.sort_unstable();
+        assert_eq!(offsets, (0..60).map(|i| i * 2).collect::<Vec<_>>());
+    }
+}
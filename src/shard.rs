@@ -0,0 +1,239 @@
+use crate::{ArrayLayout, SliceArg};
+use std::ops::Range;
+
+/// 张量在设备网格某一维上的放置方式。
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Placement {
+    /// 网格这一维上的每个设备都持有完整的数据。
+    Replicate,
+    /// 沿张量的指定轴切分到网格这一维上的设备。
+    Shard(usize),
+    /// 网格这一维上的每个设备持有部分和，规约后得到完整的数据。
+    Partial,
+}
+
+/// 在两种切分方式之间转换所需的集合通信。
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Collective {
+    /// 在网格维度上收集沿 `axis` 切分的分片。
+    AllGather {
+        /// 网格维度。
+        mesh_dim: usize,
+        /// 切分的轴。
+        axis: usize,
+    },
+    /// 在网格维度上规约部分和，结果沿 `axis` 切分。
+    ReduceScatter {
+        /// 网格维度。
+        mesh_dim: usize,
+        /// 结果切分的轴。
+        axis: usize,
+    },
+    /// 在网格维度上规约部分和，每个设备得到完整的结果。
+    AllReduce {
+        /// 网格维度。
+        mesh_dim: usize,
+    },
+    /// 在网格维度上将沿 `from` 切分的分片重新分发为沿 `to` 切分。
+    AllToAll {
+        /// 网格维度。
+        mesh_dim: usize,
+        /// 原切分轴。
+        from: usize,
+        /// 新切分轴。
+        to: usize,
+    },
+}
+
+/// 张量在设备网格上的切分方式。
+///
+/// 网格的每一维对应一个放置方式。
+/// 多个网格维度切分张量的同一轴时，靠前的网格维度先切分。
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ShardSpec {
+    mesh: Vec<usize>,
+    placements: Vec<Placement>,
+}
+
+impl ShardSpec {
+    /// 创建切分方式，`mesh` 是设备网格的形状，`placements` 是每个网格维度上的放置方式。
+    pub fn new(mesh: &[usize], placements: &[Placement]) -> Self {
+        assert_eq!(
+            mesh.len(),
+            placements.len(),
+            "mesh and placements must have the same length"
+        );
+        assert!(mesh.iter().all(|&d| d > 0), "mesh dims must be positive");
+        Self {
+            mesh: mesh.to_vec(),
+            placements: placements.to_vec(),
+        }
+    }
+
+    /// 创建在网格上完全复制的切分方式。
+    #[inline]
+    pub fn replicated(mesh: &[usize]) -> Self {
+        Self::new(mesh, &vec![Placement::Replicate; mesh.len()])
+    }
+
+    /// 设备网格的形状。
+    #[inline]
+    pub fn mesh(&self) -> &[usize] {
+        &self.mesh
+    }
+
+    /// 每个网格维度上的放置方式。
+    #[inline]
+    pub fn placements(&self) -> &[Placement] {
+        &self.placements
+    }
+
+    /// 计算网格坐标 `coords` 处的设备持有的分片在全局张量各轴上的范围。
+    ///
+    /// 不能整除时靠前的分片多分得一个元素。
+    /// `coords` 的长度必须等于网格的阶，切分的轴必须小于 `shape` 的长度，否则 panic。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{Placement::*, ShardSpec};
+    /// let spec = ShardSpec::new(&[3, 2], &[Shard(0), Shard(0)]);
+    /// // 轴 0 先切为 [0..4, 4..7, 7..10]，再切为两份
+    /// assert_eq!(spec.unshard(&[10, 8], &[0, 1]), [2..4, 0..8]);
+    /// assert_eq!(spec.unshard(&[10, 8], &[2, 0]), [7..9, 0..8]);
+    /// ```
+    pub fn unshard(&self, shape: &[usize], coords: &[usize]) -> Vec<Range<usize>> {
+        assert_eq!(coords.len(), self.mesh.len(), "coords must match mesh");
+
+        let mut ranges = shape.iter().map(|&d| 0..d).collect::<Vec<_>>();
+        for ((&n, &i), &placement) in self.mesh.iter().zip(coords).zip(&self.placements) {
+            assert!(i < n, "mesh coord {i} out of {n}");
+            if let Placement::Shard(axis) = placement {
+                assert!(
+                    axis < shape.len(),
+                    "shard axis {axis} out of {}",
+                    shape.len()
+                );
+                let range = &mut ranges[axis];
+                let d = range.len();
+                let (q, r) = (d / n, d % n);
+                let start = range.start + i * q + i.min(r);
+                *range = start..start + q + usize::from(i < r)
+            }
+        }
+        ranges
+    }
+
+    /// 计算从当前切分方式转换为 `target` 所需的集合通信。
+    ///
+    /// 从复制转换为切分或部分和只需要本地操作，不产生集合通信。
+    /// 从切分转换为部分和也不产生集合通信，每个设备把不属于自己的部分置零即可。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{Collective, Placement::*, ShardSpec};
+    /// let a = ShardSpec::new(&[4, 2], &[Partial, Shard(0)]);
+    /// let b = ShardSpec::new(&[4, 2], &[Shard(1), Shard(2)]);
+    /// assert_eq!(
+    ///     a.collectives(&b),
+    ///     [
+    ///         Collective::ReduceScatter { mesh_dim: 0, axis: 1 },
+    ///         Collective::AllToAll { mesh_dim: 1, from: 0, to: 2 },
+    ///     ]
+    /// );
+    /// ```
+    pub fn collectives(&self, target: &Self) -> Vec<Collective> {
+        use Placement::*;
+        assert_eq!(self.mesh, target.mesh, "specs must share the same mesh");
+
+        self.placements
+            .iter()
+            .zip(&target.placements)
+            .enumerate()
+            .filter_map(|(mesh_dim, (&from, &to))| match (from, to) {
+                (Shard(axis), Replicate) => Some(Collective::AllGather { mesh_dim, axis }),
+                (Shard(from), Shard(to)) if from != to => {
+                    Some(Collective::AllToAll { mesh_dim, from, to })
+                }
+                (Partial, Shard(axis)) => Some(Collective::ReduceScatter { mesh_dim, axis }),
+                (Partial, Replicate) => Some(Collective::AllReduce { mesh_dim }),
+                (_, _) => None,
+            })
+            .collect()
+    }
+}
+
+impl<const N: usize> ArrayLayout<N> {
+    /// 计算网格坐标 `coords` 处的设备持有的分片在全局布局中的布局。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, Placement::*, ShardSpec};
+    /// let layout = ArrayLayout::<2>::new(&[10, 6], &[6, 1], 0);
+    /// let spec = ShardSpec::new(&[3, 2], &[Shard(0), Shard(1)]);
+    /// let shard = layout.shard(&spec, &[2, 1]);
+    /// assert_eq!(shard.shape(), &[3, 3]);
+    /// assert_eq!(shard.strides(), &[6, 1]);
+    /// assert_eq!(shard.offset(), 45);
+    /// ```
+    pub fn shard(&self, spec: &ShardSpec, coords: &[usize]) -> Self {
+        let args = spec
+            .unshard(self.shape(), coords)
+            .into_iter()
+            .enumerate()
+            .filter(|(axis, range)| range.len() != self.shape()[*axis])
+            .map(|(axis, range)| SliceArg {
+                axis,
+                // 空分片从 0 开始，避免起点越界
+                start: if range.is_empty() { 0 } else { range.start },
+                step: 1,
+                len: range.len(),
+            })
+            .collect::<Vec<_>>();
+        self.slice_many(&args)
+    }
+}
+
+#[test]
+fn test() {
+    use crate::Endian::BigEndian;
+    use Placement::*;
+
+    let layout = ArrayLayout::<3>::new_contiguous(&[7, 5, 3], BigEndian, 1);
+    let spec = ShardSpec::new(&[2, 3, 2], &[Shard(1), Shard(0), Replicate]);
+
+    // 所有分片恰好覆盖全局布局两次（网格最后一维复制）
+    let mut count = vec![0; 7 * 5 * 3];
+    for i in 0..2 {
+        for j in 0..3 {
+            for k in 0..2 {
+                let shard = layout.shard(&spec, &[i, j, k]);
+                for n in 0..shard.num_elements() {
+                    count[shard.element_offset(n, BigEndian) as usize] += 1
+                }
+            }
+        }
+    }
+    assert!(count.iter().all(|&c| c == 2));
+
+    // 空分片
+    let spec = ShardSpec::new(&[4], &[Shard(0)]);
+    let layout = ArrayLayout::<1>::new_contiguous(&[3], BigEndian, 4);
+    assert_eq!(spec.unshard(layout.shape(), &[3]), vec![3..3]);
+    assert_eq!(layout.shard(&spec, &[3]).num_elements(), 0);
+
+    let replicated = ShardSpec::replicated(&[4]);
+    assert_eq!(
+        spec.collectives(&replicated),
+        [Collective::AllGather {
+            mesh_dim: 0,
+            axis: 0
+        }]
+    );
+    assert!(replicated.collectives(&spec).is_empty());
+    assert!(
+        spec.collectives(&ShardSpec::new(&[4], &[Partial]))
+            .is_empty()
+    );
+    let partial = ShardSpec::new(&[4], &[Partial]);
+    assert_eq!(
+        partial.collectives(&replicated),
+        [Collective::AllReduce { mesh_dim: 0 }]
+    );
+}
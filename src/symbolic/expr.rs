@@ -0,0 +1,203 @@
+use super::BindError;
+use std::{
+    collections::BTreeMap,
+    fmt,
+    ops::{Add, Mul, Neg, Sub},
+};
+
+/// 命名变量上的整系数多项式。
+///
+/// 形状和步长通常是变量的仿射表达式，连续布局的步长是形状的乘积，因此允许变量相乘。
+///
+/// ```rust
+/// # use ndarray_layout::Expr;
+/// let seq = Expr::var("seq");
+/// let stride = seq.clone() * 4096 + 2;
+/// assert_eq!(stride.to_string(), "4096*seq + 2");
+/// assert_eq!(stride.eval(&[("seq", 3)]), Ok(12290));
+/// assert_eq!((stride - seq * 4096).as_constant(), Some(2));
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Expr(BTreeMap<Vec<String>, isize>);
+
+impl Expr {
+    /// 名为 `name` 的变量。
+    pub fn var(name: &str) -> Self {
+        Self(BTreeMap::from([(vec![name.to_string()], 1)]))
+    }
+
+    /// 常量。
+    pub fn constant(val: isize) -> Self {
+        let mut map = BTreeMap::new();
+        if val != 0 {
+            map.insert(vec![], val);
+        }
+        Self(map)
+    }
+
+    /// 如果表达式是常量，返回它的值。
+    pub fn as_constant(&self) -> Option<isize> {
+        match self.0.len() {
+            0 => Some(0),
+            1 => self.0.get(&[][..]).copied(),
+            _ => None,
+        }
+    }
+
+    /// 表达式中出现的变量，按名字排序。
+    pub fn vars(&self) -> Vec<&str> {
+        let mut ans = self
+            .0
+            .keys()
+            .flatten()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        ans.sort_unstable();
+        ans.dedup();
+        ans
+    }
+
+    /// 代入变量的值求值。
+    pub fn eval(&self, vars: &[(&str, isize)]) -> Result<isize, BindError> {
+        let mut ans = 0;
+        for (monomial, &coef) in &self.0 {
+            let mut term = coef;
+            for name in monomial {
+                match vars.iter().find(|(n, _)| n == name) {
+                    Some(&(_, val)) => term *= val,
+                    None => return Err(BindError::Unbound(name.clone())),
+                }
+            }
+            ans += term
+        }
+        Ok(ans)
+    }
+
+    fn add_term(&mut self, monomial: Vec<String>, coef: isize) {
+        let entry = self.0.entry(monomial).or_insert(0);
+        *entry += coef;
+        if *entry == 0 {
+            self.0.retain(|_, c| *c != 0)
+        }
+    }
+}
+
+impl From<isize> for Expr {
+    #[inline]
+    fn from(value: isize) -> Self {
+        Self::constant(value)
+    }
+}
+
+impl From<usize> for Expr {
+    #[inline]
+    fn from(value: usize) -> Self {
+        Self::constant(value as _)
+    }
+}
+
+/// 整数字面量的默认类型。
+impl From<i32> for Expr {
+    #[inline]
+    fn from(value: i32) -> Self {
+        Self::constant(value as _)
+    }
+}
+
+impl From<&str> for Expr {
+    #[inline]
+    fn from(value: &str) -> Self {
+        Self::var(value)
+    }
+}
+
+impl<T: Into<Expr>> Add<T> for Expr {
+    type Output = Self;
+    fn add(mut self, rhs: T) -> Self {
+        for (monomial, coef) in rhs.into().0 {
+            self.add_term(monomial, coef)
+        }
+        self
+    }
+}
+
+impl<T: Into<Expr>> Sub<T> for Expr {
+    type Output = Self;
+    #[inline]
+    fn sub(self, rhs: T) -> Self {
+        self + -rhs.into()
+    }
+}
+
+impl Neg for Expr {
+    type Output = Self;
+    fn neg(mut self) -> Self {
+        self.0.values_mut().for_each(|c| *c = -*c);
+        self
+    }
+}
+
+impl<T: Into<Expr>> Mul<T> for Expr {
+    type Output = Self;
+    fn mul(self, rhs: T) -> Self {
+        let rhs = rhs.into();
+        let mut ans = Self::default();
+        for (a, &ca) in &self.0 {
+            for (b, &cb) in &rhs.0 {
+                let mut monomial = a.iter().chain(b).cloned().collect::<Vec<_>>();
+                monomial.sort_unstable();
+                ans.add_term(monomial, ca * cb)
+            }
+        }
+        ans
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // 高次项在前，常数项在后
+        let mut terms = self.0.iter().collect::<Vec<_>>();
+        terms.sort_by_key(|(m, _)| std::cmp::Reverse(m.len()));
+        if terms.is_empty() {
+            return write!(f, "0");
+        }
+        for (i, (monomial, &coef)) in terms.into_iter().enumerate() {
+            let abs = coef.unsigned_abs();
+            match (i, coef < 0) {
+                (0, true) => write!(f, "-")?,
+                (0, false) => {}
+                (_, true) => write!(f, " - ")?,
+                (_, false) => write!(f, " + ")?,
+            }
+            if monomial.is_empty() {
+                write!(f, "{abs}")?
+            } else {
+                if abs != 1 {
+                    write!(f, "{abs}*")?
+                }
+                write!(f, "{}", monomial.join("*"))?
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test() {
+    let batch = Expr::var("batch");
+    let seq = Expr::var("seq");
+
+    let expr = (batch.clone() + 1) * (seq.clone() - 2);
+    assert_eq!(expr.to_string(), "batch*seq - 2*batch + seq - 2");
+    assert_eq!(expr.vars(), ["batch", "seq"]);
+    assert_eq!(expr.eval(&[("batch", 3), ("seq", 5)]), Ok(12));
+    assert_eq!(
+        expr.eval(&[("batch", 3)]),
+        Err(BindError::Unbound("seq".into()))
+    );
+
+    assert_eq!((seq.clone() * seq.clone()).to_string(), "seq*seq");
+    assert_eq!((-seq.clone() + seq).as_constant(), Some(0));
+    assert_eq!(Expr::default().to_string(), "0");
+    assert_eq!((Expr::from(3usize) * -1isize).to_string(), "-3");
+}
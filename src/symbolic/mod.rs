@@ -0,0 +1,450 @@
+mod expr;
+
+use crate::{ArrayLayout, Endian};
+use std::{collections::BTreeSet, error::Error, fmt, iter::zip};
+
+pub use expr::Expr;
+
+/// 符号布局成立所需的约束。
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Constraint {
+    /// 两个表达式相等。
+    Eq(Expr, Expr),
+    /// 左边严格小于右边。
+    Lt(Expr, Expr),
+}
+
+impl Constraint {
+    /// 代入变量的值检查约束是否成立。
+    pub fn check(&self, vars: &[(&str, isize)]) -> Result<bool, BindError> {
+        Ok(match self {
+            Self::Eq(lhs, rhs) => lhs.eval(vars)? == rhs.eval(vars)?,
+            Self::Lt(lhs, rhs) => lhs.eval(vars)? < rhs.eval(vars)?,
+        })
+    }
+
+    /// 不代入变量即可判定时返回约束是否成立。
+    fn decide(&self) -> Option<bool> {
+        match self {
+            Self::Eq(lhs, rhs) => (lhs.clone() - rhs.clone()).as_constant().map(|d| d == 0),
+            Self::Lt(lhs, rhs) => (rhs.clone() - lhs.clone()).as_constant().map(|d| d > 0),
+        }
+    }
+}
+
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Eq(lhs, rhs) => write!(f, "{lhs} == {rhs}"),
+            Self::Lt(lhs, rhs) => write!(f, "{lhs} < {rhs}"),
+        }
+    }
+}
+
+/// 绑定变量的错误。
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum BindError {
+    /// 变量未绑定。
+    Unbound(String),
+    /// 约束不成立。
+    Violated(Constraint),
+    /// 形状长度为负。
+    NegativeShape {
+        /// 出错的轴。
+        axis: usize,
+        /// 求得的长度。
+        value: isize,
+    },
+}
+
+impl fmt::Display for BindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unbound(name) => write!(f, "variable `{name}` is not bound"),
+            Self::Violated(c) => write!(f, "constraint `{c}` is violated"),
+            Self::NegativeShape { axis, value } => write!(f, "axis {axis} has length {value}"),
+        }
+    }
+}
+
+impl Error for BindError {}
+
+/// 形状、步长和偏移是命名变量表达式的布局。
+///
+/// 变换在符号上进行，只有代入变量后才能判定的前提条件被记录为约束，
+/// 由 [`SymbolicLayout::bind`] 在生成具体布局时检查。
+///
+/// ```rust
+/// # use ndarray_layout::{ArrayLayout, Endian::BigEndian, Expr, SymbolicLayout};
+/// let shape = [Expr::var("batch"), Expr::var("seq"), 32.into(), 128.into()];
+/// let layout = SymbolicLayout::new_contiguous(&shape, BigEndian, 2)
+///     .transpose(&[2, 1])
+///     .index(0, 1);
+/// assert_eq!(layout.strides()[0].to_string(), "256");
+/// assert_eq!(layout.strides()[1].to_string(), "8192");
+/// assert_eq!(layout.offset().to_string(), "8192*seq");
+///
+/// let concrete = layout.bind::<4>(&[("batch", 4), ("seq", 128)]).unwrap();
+/// let expected = ArrayLayout::<4>::new_contiguous(&[4, 128, 32, 128], BigEndian, 2)
+///     .transpose(&[2, 1])
+///     .index(0, 1);
+/// assert!(concrete == expected);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct SymbolicLayout {
+    shape: Vec<Expr>,
+    strides: Vec<Expr>,
+    offset: Expr,
+    constraints: Vec<Constraint>,
+}
+
+impl<const N: usize> From<&ArrayLayout<N>> for SymbolicLayout {
+    fn from(value: &ArrayLayout<N>) -> Self {
+        Self {
+            shape: value.shape().iter().map(|&d| d.into()).collect(),
+            strides: value.strides().iter().map(|&s| s.into()).collect(),
+            offset: value.offset().into(),
+            constraints: vec![],
+        }
+    }
+}
+
+impl SymbolicLayout {
+    /// 使用给定的形状、步长和偏移创建符号布局。
+    pub fn new(shape: &[Expr], strides: &[Expr], offset: Expr) -> Self {
+        assert_eq!(
+            shape.len(),
+            strides.len(),
+            "shape and strides must have the same length"
+        );
+        Self {
+            shape: shape.to_vec(),
+            strides: strides.to_vec(),
+            offset,
+            constraints: vec![],
+        }
+    }
+
+    /// 使用给定的形状创建连续的符号布局。
+    pub fn new_contiguous(shape: &[Expr], endian: Endian, element_size: usize) -> Self {
+        let mut strides = vec![Expr::default(); shape.len()];
+        let mut mul = Expr::from(element_size);
+        let mut push = |i: usize| {
+            strides[i] = mul.clone();
+            mul = mul.clone() * shape[i].clone();
+        };
+        match endian {
+            Endian::BigEndian => (0..shape.len()).rev().for_each(&mut push),
+            Endian::LittleEndian => (0..shape.len()).for_each(&mut push),
+        }
+        Self::new(shape, &strides, Expr::default())
+    }
+
+    /// 布局的维数。
+    #[inline]
+    pub fn ndim(&self) -> usize {
+        self.shape.len()
+    }
+
+    /// 布局的形状。
+    #[inline]
+    pub fn shape(&self) -> &[Expr] {
+        &self.shape
+    }
+
+    /// 布局的步长。
+    #[inline]
+    pub fn strides(&self) -> &[Expr] {
+        &self.strides
+    }
+
+    /// 布局的偏移。
+    #[inline]
+    pub fn offset(&self) -> &Expr {
+        &self.offset
+    }
+
+    /// 变换过程中记录的、不代入变量无法判定的约束。
+    #[inline]
+    pub fn constraints(&self) -> &[Constraint] {
+        &self.constraints
+    }
+
+    /// 代入变量的值，检查约束并生成具体布局。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{BindError, Expr, SymbolicLayout};
+    /// let layout = SymbolicLayout::new(&["n".into()], &[4isize.into()], 0isize.into()).index(0, 5);
+    /// assert_eq!(layout.constraints()[0].to_string(), "5 < n");
+    /// assert!(layout.bind::<1>(&[("n", 8)]).is_ok());
+    /// assert!(matches!(layout.bind::<1>(&[("n", 4)]), Err(BindError::Violated(_))));
+    /// assert_eq!(layout.bind::<1>(&[]).err(), Some(BindError::Unbound("n".into())));
+    /// ```
+    pub fn bind<const N: usize>(
+        &self,
+        vars: &[(&str, isize)],
+    ) -> Result<ArrayLayout<N>, BindError> {
+        let shape = self
+            .shape
+            .iter()
+            .enumerate()
+            .map(|(axis, d)| match d.eval(vars)? {
+                value @ ..0 => Err(BindError::NegativeShape { axis, value }),
+                value => Ok(value as usize),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let strides = self
+            .strides
+            .iter()
+            .map(|s| s.eval(vars))
+            .collect::<Result<Vec<_>, _>>()?;
+        let offset = self.offset.eval(vars)?;
+        for c in &self.constraints {
+            if !c.check(vars)? {
+                return Err(BindError::Violated(c.clone()));
+            }
+        }
+        Ok(ArrayLayout::new(&shape, &strides, offset))
+    }
+
+    /// 转置变换，语义同 [`ArrayLayout::transpose`]。
+    pub fn transpose(&self, perm: &[usize]) -> Self {
+        let perm_ = perm.iter().collect::<BTreeSet<_>>();
+        assert_eq!(perm_.len(), perm.len());
+
+        let mut ans = self.clone();
+        for (&i, &j) in zip(perm_, perm) {
+            ans.shape[i] = self.shape[j].clone();
+            ans.strides[i] = self.strides[j].clone();
+        }
+        ans
+    }
+
+    /// 索引变换，语义同 [`ArrayLayout::index`]，要求 `index < shape[axis]`。
+    pub fn index(&self, axis: usize, index: impl Into<Expr>) -> Self {
+        let index = index.into();
+        let mut ans = self.clone();
+        let d = ans.shape.remove(axis);
+        let s = ans.strides.remove(axis);
+        ans.offset = ans.offset + index.clone() * s;
+        ans.require(Constraint::Lt(index, d));
+        ans
+    }
+
+    /// 切片变换，语义同 [`ArrayLayout::slice`]。
+    ///
+    /// 符号长度无法截断，因此要求切片完全落在原维度内，这一条件作为约束记录。
+    pub fn slice(
+        &self,
+        axis: usize,
+        start: impl Into<Expr>,
+        step: isize,
+        len: impl Into<Expr>,
+    ) -> Self {
+        let start = start.into();
+        let len = len.into();
+        let mut ans = self.clone();
+        let d = ans.shape[axis].clone();
+        let s = ans.strides[axis].clone();
+
+        ans.offset = ans.offset + start.clone() * s.clone();
+        ans.shape[axis] = len.clone();
+        ans.strides[axis] = s * step;
+
+        let span = (len - 1isize) * step.unsigned_abs();
+        use std::cmp::Ordering::*;
+        match step.cmp(&0) {
+            Greater => ans.require(Constraint::Lt(start + span, d)),
+            Equal => ans.require(Constraint::Lt(start, d)),
+            Less => {
+                ans.require(Constraint::Lt(start.clone(), d));
+                ans.require(Constraint::Lt(span, start + 1isize))
+            }
+        }
+        ans
+    }
+
+    /// 广播变换，语义同 [`ArrayLayout::broadcast`]，要求原维度长度为 1 或步长为 0。
+    pub fn broadcast(&self, axis: usize, times: impl Into<Expr>) -> Self {
+        let mut ans = self.clone();
+        if ans.strides[axis].as_constant() != Some(0) {
+            let d = std::mem::replace(&mut ans.shape[axis], times.into());
+            ans.require(Constraint::Eq(d, 1isize.into()));
+        } else {
+            ans.shape[axis] = times.into();
+        }
+        ans.strides[axis] = Expr::default();
+        ans
+    }
+
+    /// 大端分块变换，语义同 [`ArrayLayout::tile_be`]，要求分块之积等于原维度长度。
+    pub fn tile_be(&self, axis: usize, tiles: &[Expr]) -> Self {
+        self.tile(axis, tiles, Endian::BigEndian)
+    }
+
+    /// 小端分块变换，语义同 [`ArrayLayout::tile_le`]，要求分块之积等于原维度长度。
+    pub fn tile_le(&self, axis: usize, tiles: &[Expr]) -> Self {
+        self.tile(axis, tiles, Endian::LittleEndian)
+    }
+
+    /// 大端合并变换，语义同 [`ArrayLayout::merge_be`]。
+    ///
+    /// 相邻维度的步长关系能直接判定不成立时返回 `None`，无法判定时记录为约束。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::SymbolicLayout;
+    /// // 每行 `n` 个元素，行距为 `ld` 字节
+    /// let layout = SymbolicLayout::new(&["m".into(), "n".into()], &["ld".into(), 2.into()], 0.into());
+    /// let merged = layout.merge_be(0, 2).unwrap();
+    /// assert_eq!(merged.shape()[0].to_string(), "m*n");
+    /// assert_eq!(merged.constraints()[0].to_string(), "ld == 2*n");
+    /// assert!(merged.bind::<1>(&[("m", 3), ("n", 5), ("ld", 10)]).is_ok());
+    /// assert!(merged.bind::<1>(&[("m", 3), ("n", 5), ("ld", 16)]).is_err());
+    /// ```
+    pub fn merge_be(&self, start: usize, len: usize) -> Option<Self> {
+        self.merge(start, len, Endian::BigEndian)
+    }
+
+    /// 小端合并变换，语义同 [`ArrayLayout::merge_le`]。
+    pub fn merge_le(&self, start: usize, len: usize) -> Option<Self> {
+        self.merge(start, len, Endian::LittleEndian)
+    }
+
+    fn tile(&self, axis: usize, tiles: &[Expr], endian: Endian) -> Self {
+        let mut ans = self.clone();
+        let d = ans.shape.remove(axis);
+        let s = ans.strides.remove(axis);
+
+        let mut strides = vec![Expr::default(); tiles.len()];
+        let mut mul = s;
+        let mut push = |i: usize| {
+            strides[i] = mul.clone();
+            mul = mul.clone() * tiles[i].clone();
+        };
+        match endian {
+            Endian::BigEndian => (0..tiles.len()).rev().for_each(&mut push),
+            Endian::LittleEndian => (0..tiles.len()).for_each(&mut push),
+        }
+        let product = tiles
+            .iter()
+            .fold(Expr::from(1isize), |acc, t| acc * t.clone());
+
+        ans.shape.splice(axis..axis, tiles.iter().cloned());
+        ans.strides.splice(axis..axis, strides);
+        ans.require(Constraint::Eq(product, d));
+        ans
+    }
+
+    fn merge(&self, start: usize, len: usize, endian: Endian) -> Option<Self> {
+        let end = start + len;
+        assert!(end <= self.ndim());
+        if len == 0 {
+            return Some(self.clone());
+        }
+
+        let mut pairs = zip(&self.shape[start..end], &self.strides[start..end])
+            .filter(|(d, _)| d.as_constant() != Some(1))
+            .map(|(d, s)| (d.clone(), s.clone()))
+            .collect::<Vec<_>>();
+        if endian == Endian::BigEndian {
+            pairs.reverse()
+        }
+
+        let mut ans = self.clone();
+        let (d, s) = match pairs.split_first() {
+            Some(((d, s), tail)) => {
+                let mut d = d.clone();
+                for (d_, s_) in tail {
+                    let c = Constraint::Eq(s_.clone(), s.clone() * d.clone());
+                    if c.decide() == Some(false) {
+                        return None;
+                    }
+                    ans.require(c);
+                    d = d * d_.clone()
+                }
+                (d, s.clone())
+            }
+            None => (1isize.into(), Expr::default()),
+        };
+        ans.shape.splice(start..end, [d]);
+        ans.strides.splice(start..end, [s]);
+        Some(ans)
+    }
+
+    /// 记录约束，可直接判定成立的约束被忽略，可直接判定不成立的约束导致 panic。
+    fn require(&mut self, c: Constraint) {
+        match c.decide() {
+            Some(true) => {}
+            Some(false) => panic!("constraint `{c}` never holds"),
+            None if self.constraints.contains(&c) => {}
+            None => self.constraints.push(c),
+        }
+    }
+}
+
+#[test]
+fn test() {
+    use Endian::BigEndian;
+
+    let shape = [Expr::var("batch"), Expr::var("seq"), 32.into(), 128.into()];
+    let layout = SymbolicLayout::new_contiguous(&shape, BigEndian, 2);
+    assert_eq!(layout.strides()[0].to_string(), "8192*seq");
+
+    // [batch, seq, 32, 128] -> [batch, seq, 4096] -> [batch, seq, 2, 2048] -> [batch, 2, seq, 2048]
+    let symbolic = layout
+        .merge_be(2, 2)
+        .unwrap()
+        .tile_be(2, &[2.into(), 2048.into()])
+        .transpose(&[2, 1])
+        .slice(2, 1, 2, Expr::var("half"))
+        .broadcast(0, 1isize)
+        .index(1, 1);
+    let concrete = ArrayLayout::<4>::new_contiguous(&[1, 128, 32, 128], BigEndian, 2)
+        .merge_be(2, 2)
+        .unwrap()
+        .tile_be(2, &[2, 2048])
+        .transpose(&[2, 1])
+        .slice(2, 1, 2, 64)
+        .broadcast(0, 1)
+        .index(1, 1);
+    let vars = [("batch", 1), ("seq", 128), ("half", 64)];
+    assert!(symbolic.bind::<4>(&vars).unwrap() == concrete);
+
+    // 切片超出范围，广播非 1 的维度
+    let vars = [("batch", 1), ("seq", 128), ("half", 65)];
+    assert!(matches!(
+        symbolic.bind::<4>(&vars),
+        Err(BindError::Violated(_))
+    ));
+    let vars = [("batch", 2), ("seq", 128), ("half", 64)];
+    assert_eq!(
+        symbolic.bind::<4>(&vars).err(),
+        Some(BindError::Violated(Constraint::Eq(
+            Expr::var("batch"),
+            1isize.into()
+        )))
+    );
+
+    // 步长关系直接判定不成立
+    let layout = SymbolicLayout::new(
+        &["m".into(), "n".into()],
+        &[Expr::var("n") + 1, 1isize.into()],
+        Expr::default(),
+    );
+    assert!(layout.merge_be(0, 2).is_none());
+    let layout = SymbolicLayout::new(
+        &["m".into(), "n".into()],
+        &["ld".into(), 1isize.into()],
+        Expr::default(),
+    );
+    let merged = layout.merge_be(0, 2).unwrap();
+    assert_eq!(
+        merged.constraints(),
+        [Constraint::Eq("ld".into(), "n".into())]
+    );
+    assert!(merged.bind::<2>(&[("m", 3), ("n", 4), ("ld", 4)]).is_ok());
+    assert!(merged.bind::<2>(&[("m", 3), ("n", 4), ("ld", 8)]).is_err());
+
+    let concrete = ArrayLayout::<2>::new(&[3, 4], &[-4, 1], 8);
+    assert!(SymbolicLayout::from(&concrete).bind::<2>(&[]).unwrap() == concrete);
+}
@@ -0,0 +1,254 @@
+use crate::{ArrayLayout, Transform};
+use std::fmt;
+
+/// 记录变换历史的布局。
+///
+/// 每次变换都被记录为一个 [`Transform`]，可以在另一个基础布局上重放，
+/// 或格式化为文本配方，由 [`Transform::parse_recipe`] 解析后在其他进程中重建。
+///
+/// ```rust
+/// # use ndarray_layout::{ArrayLayout, Endian::BigEndian, TracedLayout, Transform};
+/// let base = ArrayLayout::<4>::new_contiguous(&[2, 5, 4096], BigEndian, 2);
+/// let traced = TracedLayout::new(base)
+///     .tile_be(2, &[32, 128])
+///     .transpose(&[2, 1])
+///     .index(0, 1);
+/// let recipe = traced.to_string();
+/// assert_eq!(recipe, "tile_be(2, [32, 128]); transpose([2, 1]); index(0, 1)");
+///
+/// // 在另一个进程中以不同的序列长度重建
+/// let ops = Transform::parse_recipe(&recipe).unwrap();
+/// let base = ArrayLayout::<4>::new_contiguous(&[2, 7, 4096], BigEndian, 2);
+/// let layout = Transform::replay(&ops, &base).unwrap();
+/// assert_eq!(layout.shape(), &[32, 7, 128]);
+/// ```
+#[derive(Clone)]
+pub struct TracedLayout<const N: usize> {
+    base: ArrayLayout<N>,
+    layout: ArrayLayout<N>,
+    ops: Vec<Transform>,
+}
+
+impl<const N: usize> From<ArrayLayout<N>> for TracedLayout<N> {
+    #[inline]
+    fn from(value: ArrayLayout<N>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<const N: usize> TracedLayout<N> {
+    /// 从基础布局开始记录。
+    #[inline]
+    pub fn new(base: ArrayLayout<N>) -> Self {
+        Self {
+            layout: base.clone(),
+            base,
+            ops: vec![],
+        }
+    }
+
+    /// 基础布局。
+    #[inline]
+    pub fn base(&self) -> &ArrayLayout<N> {
+        &self.base
+    }
+
+    /// 当前布局。
+    #[inline]
+    pub fn layout(&self) -> &ArrayLayout<N> {
+        &self.layout
+    }
+
+    /// 取出当前布局。
+    #[inline]
+    pub fn into_layout(self) -> ArrayLayout<N> {
+        self.layout
+    }
+
+    /// 已记录的变换。
+    #[inline]
+    pub fn ops(&self) -> &[Transform] {
+        &self.ops
+    }
+
+    /// 应用并记录变换，变换失败时返回 `None`。
+    pub fn apply(&self, op: Transform) -> Option<Self> {
+        let layout = op.apply(&self.layout)?;
+        let mut ops = self.ops.clone();
+        ops.push(op);
+        Some(Self {
+            base: self.base.clone(),
+            layout,
+            ops,
+        })
+    }
+
+    /// 在另一个基础布局上重放记录的变换。
+    #[inline]
+    pub fn replay<const M: usize>(&self, base: &ArrayLayout<M>) -> Option<ArrayLayout<M>> {
+        Transform::replay(&self.ops, base)
+    }
+
+    /// 撤销最后一个变换，没有变换时返回 `None`。
+    pub fn undo(&self) -> Option<Self> {
+        let (_, ops) = self.ops.split_last()?;
+        Some(Self {
+            base: self.base.clone(),
+            layout: Transform::replay(ops, &self.base).unwrap(),
+            ops: ops.to_vec(),
+        })
+    }
+
+    /// 把当前布局映射回基础布局的逆变换，见 [`Transform::invert_chain`]。
+    #[inline]
+    pub fn inverse(&self) -> Option<Vec<Transform>> {
+        Transform::invert_chain(&self.ops, &self.base)
+    }
+
+    /// 见 [`ArrayLayout::index`]。
+    #[inline]
+    pub fn index(&self, axis: usize, index: usize) -> Self {
+        self.apply(Transform::Index { axis, index }).unwrap()
+    }
+
+    /// 见 [`ArrayLayout::slice`]。
+    #[inline]
+    pub fn slice(&self, axis: usize, start: usize, step: isize, len: usize) -> Self {
+        self.apply(Transform::Slice {
+            axis,
+            start,
+            step,
+            len,
+        })
+        .unwrap()
+    }
+
+    /// 见 [`ArrayLayout::tile_be`]。
+    #[inline]
+    pub fn tile_be(&self, axis: usize, tiles: &[usize]) -> Self {
+        let tiles = tiles.to_vec();
+        self.apply(Transform::TileBe { axis, tiles }).unwrap()
+    }
+
+    /// 见 [`ArrayLayout::tile_le`]。
+    #[inline]
+    pub fn tile_le(&self, axis: usize, tiles: &[usize]) -> Self {
+        let tiles = tiles.to_vec();
+        self.apply(Transform::TileLe { axis, tiles }).unwrap()
+    }
+
+    /// 见 [`ArrayLayout::merge_be`]。
+    #[inline]
+    pub fn merge_be(&self, start: usize, len: usize) -> Option<Self> {
+        self.apply(Transform::MergeBe { start, len })
+    }
+
+    /// 见 [`ArrayLayout::merge_le`]。
+    #[inline]
+    pub fn merge_le(&self, start: usize, len: usize) -> Option<Self> {
+        self.apply(Transform::MergeLe { start, len })
+    }
+
+    /// 见 [`ArrayLayout::merge_free`]。
+    #[inline]
+    pub fn merge_free(&self, start: usize, len: usize) -> Option<Self> {
+        self.apply(Transform::MergeFree { start, len })
+    }
+
+    /// 见 [`ArrayLayout::transpose`]。
+    #[inline]
+    pub fn transpose(&self, perm: &[usize]) -> Self {
+        let perm = perm.to_vec();
+        self.apply(Transform::Transpose { perm }).unwrap()
+    }
+
+    /// 见 [`ArrayLayout::broadcast`]。
+    #[inline]
+    pub fn broadcast(&self, axis: usize, times: usize) -> Self {
+        self.apply(Transform::Broadcast { axis, times }).unwrap()
+    }
+
+    /// 见 [`ArrayLayout::windows`]。
+    #[inline]
+    pub fn windows(&self, axis: usize, size: usize, step: usize) -> Self {
+        self.apply(Transform::Windows { axis, size, step }).unwrap()
+    }
+
+    /// 见 [`ArrayLayout::split`]，每一份记录为一个切片。
+    pub fn split<'a>(&'a self, axis: usize, parts: &'a [usize]) -> impl Iterator<Item = Self> + 'a {
+        assert_eq!(self.layout.shape()[axis], parts.iter().sum());
+        let mut start = 0;
+        parts.iter().map(move |&len| {
+            // 切片要求起点在维度内，空的部分从 0 开始
+            let part = self.slice(axis, if len == 0 { 0 } else { start }, 1, len);
+            start += len;
+            part
+        })
+    }
+}
+
+impl<const N: usize> fmt::Display for TracedLayout<N> {
+    /// 以 `; ` 分隔的变换配方。
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, op) in self.ops.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?
+            }
+            write!(f, "{op}")?
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test() {
+    use crate::Endian::BigEndian;
+
+    let base = ArrayLayout::<4>::new_contiguous(&[4, 6], BigEndian, 4);
+    let traced = TracedLayout::from(base.clone())
+        .slice(0, 3, -1, 4)
+        .tile_le(1, &[2, 3])
+        .index(2, 1)
+        .merge_le(0, 1)
+        .unwrap()
+        .transpose(&[1, 0]);
+    assert!(traced.base() == &base);
+    assert_eq!(traced.ops().len(), 5);
+    assert!(traced.replay(&base).unwrap() == *traced.layout());
+
+    // 重放到内联维数不同的布局
+    let other = ArrayLayout::<2>::new_contiguous(&[4, 6], BigEndian, 4);
+    assert!(traced.replay(&other).unwrap() == traced.layout().to_inline_size::<2>());
+
+    // 合并失败时返回 `None`
+    assert!(traced.merge_be(0, 2).is_none());
+
+    let recipe = traced.to_string();
+    assert_eq!(
+        recipe,
+        "slice(0, 3, -1, 4); tile_le(1, [2, 3]); index(2, 1); merge_le(0, 1); transpose([1, 0])"
+    );
+    assert_eq!(Transform::parse_recipe(&recipe).unwrap(), traced.ops());
+
+    let inverse = traced.inverse().unwrap();
+    assert!(Transform::replay(&inverse, traced.layout()).unwrap() == base);
+    let undone = traced.undo().unwrap();
+    assert_eq!(undone.ops(), &traced.ops()[..4]);
+    assert!(undone.transpose(&[1, 0]).layout() == traced.layout());
+
+    // 滑动窗口和切分也能记录并求逆
+    let traced = TracedLayout::new(base.clone()).windows(1, 3, 2);
+    let parts = traced.split(0, &[1, 3]).collect::<Vec<_>>();
+    assert_eq!(parts[1].to_string(), "windows(1, 3, 2); slice(0, 1, 1, 3)");
+    assert!(parts[1].layout() == &base.windows(1, 3, 2).split(0, &[1, 3]).nth(1).unwrap());
+    let inverse = parts[1].inverse().unwrap();
+    assert_eq!(
+        inverse.iter().map(|op| op.to_string()).collect::<Vec<_>>(),
+        ["unslice(0, 1, 1, 4)", "unwindows(1, 6)"]
+    );
+    assert!(Transform::replay(&inverse, parts[1].layout()).unwrap() == base);
+
+    let traced = TracedLayout::new(base);
+    assert!(traced.to_string().is_empty());
+    assert!(traced.undo().is_none());
+}
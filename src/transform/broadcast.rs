@@ -23,6 +23,23 @@ impl<const N: usize> ArrayLayout<N> {
         self.broadcast_many(&[BroadcastArg { axis, times }])
     }
 
+    /// 原地进行广播变换，见 [`ArrayLayout::broadcast`]。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::ArrayLayout;
+    /// let mut layout = ArrayLayout::<3>::new(&[1, 5, 2], &[10, 2, 1], 0);
+    /// layout.broadcast_inplace(0, 10);
+    /// assert_eq!(layout.shape(), &[10, 5, 2]);
+    /// assert_eq!(layout.strides(), &[0, 2, 1]);
+    /// ```
+    pub fn broadcast_inplace(&mut self, axis: usize, times: usize) -> &mut Self {
+        let mut content = self.content_mut();
+        assert!(content.shape()[axis] == 1 || content.strides()[axis] == 0);
+        content.set_shape(axis, times);
+        content.set_stride(axis, 0);
+        self
+    }
+
     /// 一次对多个阶进行广播变换。
     pub fn broadcast_many(&self, args: &[BroadcastArg]) -> Self {
         let mut ans = self.clone();
@@ -25,6 +25,31 @@ impl<const N: usize> ArrayLayout<N> {
         self.index_many(&[IndexArg { axis, index }])
     }
 
+    /// 原地进行索引变换，见 [`ArrayLayout::index`]。
+    ///
+    /// 降阶后仍能内联存储时复用内联空间，否则原地缩小堆上的空间。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::ArrayLayout;
+    /// let mut layout = ArrayLayout::<3>::new(&[2, 3, 4], &[12, 4, 1], 0);
+    /// layout.index_inplace(1, 2);
+    /// assert_eq!(layout.shape(), &[2, 4]);
+    /// assert_eq!(layout.strides(), &[12, 1]);
+    /// assert_eq!(layout.offset(), 8);
+    /// ```
+    pub fn index_inplace(&mut self, axis: usize, index: usize) -> &mut Self {
+        let mut content = self.content_mut();
+        let d = content.shape().get(axis).copied();
+        assert!(
+            d.is_some_and(|d| index < d),
+            "Invalid index arg: {:?}",
+            IndexArg { axis, index }
+        );
+        content.set_offset(content.offset() + index as isize * content.strides()[axis]);
+        self.splice_dims(axis..axis + 1, 0);
+        self
+    }
+
     /// 一次对多个阶进行索引变换。
     pub fn index_many(&self, mut args: &[IndexArg]) -> Self {
         let content = self.content();
@@ -78,4 +103,17 @@ fn test() {
     assert_eq!(layout.shape(), &[2, 4]);
     assert_eq!(layout.strides(), &[12, 1]);
     assert_eq!(layout.offset(), 12);
+
+    // 在内联和堆上存储之间原地变换
+    let mut layout = ArrayLayout::<2>::new(&[2, 3, 4, 5], &[60, 20, 5, 1], 0);
+    layout.index_inplace(3, 1).index_inplace(0, 1);
+    assert!(layout == ArrayLayout::new(&[3, 4], &[20, 5], 61));
+    layout
+        .tile_be_inplace(1, &[2, 2])
+        .tile_le_inplace(0, &[1, 3]);
+    assert!(layout == ArrayLayout::new(&[1, 3, 2, 2], &[20, 20, 10, 5], 61));
+    assert!(layout.merge_be_inplace(2, 2) && layout.merge_le_inplace(0, 2));
+    assert!(layout == ArrayLayout::new(&[3, 4], &[20, 5], 61));
+    layout.index_inplace(0, 0).index_inplace(0, 3);
+    assert!(layout == ArrayLayout::new(&[], &[], 76));
 }
@@ -70,6 +70,96 @@ impl<const N: usize> ArrayLayout<N> {
         }])
     }
 
+    /// 原地进行大端合并变换，见 [`ArrayLayout::merge_be`]。
+    /// 无法合并时返回 `false`，布局不变。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::ArrayLayout;
+    /// let mut layout = ArrayLayout::<3>::new(&[2, 3, 4], &[16, 4, 1], 0);
+    /// assert!(!layout.merge_be_inplace(0, 3));
+    /// assert_eq!(layout.shape(), &[2, 3, 4]);
+    /// assert!(layout.merge_be_inplace(1, 2));
+    /// assert_eq!(layout.shape(), &[2, 12]);
+    /// assert_eq!(layout.strides(), &[16, 1]);
+    /// ```
+    #[inline]
+    pub fn merge_be_inplace(&mut self, start: usize, len: usize) -> bool {
+        self.merge_inplace(start, len, Some(Endian::BigEndian))
+    }
+
+    /// 原地进行小端合并变换，见 [`ArrayLayout::merge_le`]。
+    /// 无法合并时返回 `false`，布局不变。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::ArrayLayout;
+    /// let mut layout = ArrayLayout::<3>::new(&[4, 3, 2], &[1, 4, 12], 0);
+    /// assert!(!layout.merge_be_inplace(0, 3));
+    /// assert!(layout.merge_le_inplace(0, 3));
+    /// assert_eq!(layout.shape(), &[24]);
+    /// assert_eq!(layout.strides(), &[1]);
+    /// ```
+    #[inline]
+    pub fn merge_le_inplace(&mut self, start: usize, len: usize) -> bool {
+        self.merge_inplace(start, len, Some(Endian::LittleEndian))
+    }
+
+    /// 原地进行任意合并变换，见 [`ArrayLayout::merge_free`]。
+    /// 无法合并时返回 `false`，布局不变。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::ArrayLayout;
+    /// let mut layout = ArrayLayout::<3>::new(&[3, 2, 4], &[4, 12, 1], 0);
+    /// assert!(layout.merge_free_inplace(0, 3));
+    /// assert_eq!(layout.shape(), &[24]);
+    /// assert_eq!(layout.strides(), &[1]);
+    /// ```
+    #[inline]
+    pub fn merge_free_inplace(&mut self, start: usize, len: usize) -> bool {
+        self.merge_inplace(start, len, None)
+    }
+
+    fn merge_inplace(&mut self, start: usize, len: usize, endian: Option<Endian>) -> bool {
+        if len == 0 {
+            return true;
+        }
+        let content = self.content();
+        let range = start..start + len;
+        let shape = &content.shape()[range.clone()];
+        let strides = &content.strides()[range.clone()];
+        // 按合并顺序依次检查长度不为 1 的维度，不分配空间
+        let dims = zip(shape.iter().copied(), strides.iter().copied()).filter(|&(d, _)| d > 1);
+        let merged = if shape.contains(&0) {
+            // 有长度为 0 的维度时合并为空的维度
+            Some((0, 0))
+        } else {
+            match endian {
+                Some(Endian::BigEndian) => merge_chain(dims.rev()),
+                Some(Endian::LittleEndian) => merge_chain(dims),
+                None => {
+                    // 每次选出步长绝对值更大的下一个维度，相等时按序号
+                    let key = |i: usize| (strides[i].unsigned_abs(), i);
+                    let mut last = None;
+                    merge_chain(std::iter::from_fn(|| {
+                        let i = (0..len)
+                            .filter(|&i| shape[i] > 1 && last.is_none_or(|last| key(i) > last))
+                            .min_by_key(|&i| key(i))?;
+                        last = Some(key(i));
+                        Some((shape[i], strides[i]))
+                    }))
+                }
+            }
+        };
+        let Some((d, s)) = merged else {
+            return false;
+        };
+
+        self.splice_dims(range, 1);
+        let mut content = self.content_mut();
+        content.set_shape(start, d);
+        content.set_stride(start, s);
+        true
+    }
+
     /// 一次对多个阶进行合并变换。
     pub fn merge_many(&self, args: &[MergeArg]) -> Option<Self> {
         let content = self.content();
@@ -101,16 +191,22 @@ impl<const N: usize> ArrayLayout<N> {
                 push(shape[j], strides[j]);
             }
 
+            // 有长度为 0 的维度时合并为空的维度
+            if shape[start..end].contains(&0) {
+                push(0, 0);
+                last_end = end;
+                continue;
+            }
+
             let mut pairs = Vec::with_capacity(len);
             for (&d, &s) in zip(&shape[start..end], &strides[start..end]) {
-                match d {
-                    0 => todo!(),
-                    1 => {}
-                    _ => pairs.push((d, s)),
+                if d > 1 {
+                    pairs.push((d, s))
                 }
             }
             if pairs.is_empty() {
                 push(1, 0);
+                last_end = end;
                 continue;
             }
             match endian {
@@ -141,6 +237,21 @@ impl<const N: usize> ArrayLayout<N> {
     }
 }
 
+/// 按顺序合并维度，返回合并后的长度和步长，无法合并时返回 `None`。
+fn merge_chain(mut dims: impl Iterator<Item = (usize, isize)>) -> Option<(usize, isize)> {
+    let Some((mut d, s)) = dims.next() else {
+        return Some((1, 0));
+    };
+    for (d_, s_) in dims {
+        if s_ == s * d as isize {
+            d *= d_
+        } else {
+            return None;
+        }
+    }
+    Some((d, s))
+}
+
 #[test]
 fn test_merge() {
     let layout = ArrayLayout::<3>::new(&[16, 1, 4], &[16, 768, 4], 0)
@@ -149,4 +260,25 @@ fn test_merge() {
     assert_eq!(layout.shape(), &[16, 4]);
     assert_eq!(layout.strides(), &[16, 4]);
     assert_eq!(layout.offset(), 0);
+
+    let layout = ArrayLayout::<3>::new(&[2, 1, 1], &[1, 7, 7], 0)
+        .merge_be(1, 2)
+        .unwrap();
+    assert_eq!(layout.shape(), &[2, 1]);
+    assert_eq!(layout.strides(), &[1, 0]);
+
+    // 合并长度为 0 的维度，原地合并与逐个合并结果相同
+    let layout = ArrayLayout::<3>::new(&[2, 0, 3, 5], &[60, 12, 4, 1], 0);
+    for (start, len) in [(0, 2), (1, 2), (1, 3), (0, 4)] {
+        for endian in [Some(Endian::BigEndian), Some(Endian::LittleEndian), None] {
+            let eager = layout
+                .merge_many(&[MergeArg { start, len, endian }])
+                .unwrap();
+            let mut inplace = layout.clone();
+            assert!(inplace.merge_inplace(start, len, endian));
+            assert!(inplace == eager);
+            assert_eq!(eager.shape()[start], 0);
+            assert_eq!(eager.strides()[start], 0);
+        }
+    }
 }
@@ -1,14 +1,18 @@
 ﻿mod broadcast;
 mod index;
 mod merge;
+mod op;
 mod slice;
 mod split;
 mod tile;
 mod transpose;
+mod windows;
 
 pub use broadcast::BroadcastArg;
 pub use index::IndexArg;
 pub use merge::MergeArg;
+pub use op::{ParseTransformError, Transform};
 pub use slice::SliceArg;
 pub use split::Split;
 pub use tile::TileArg;
+pub use windows::WindowArg;
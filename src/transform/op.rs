@@ -0,0 +1,757 @@
+use crate::ArrayLayout;
+use std::{error::Error, fmt, str::FromStr};
+
+/// 单个布局变换及其参数。
+///
+/// 变换可以格式化为与方法调用相同的文本，并从这种文本中解析。
+/// [`ArrayLayout::split`] 的每一份都是一个切片，记录为 [`Transform::Slice`]。
+///
+/// ```rust
+/// # use ndarray_layout::{ArrayLayout, Transform};
+/// let op = "tile_be(0, [2, 3])".parse::<Transform>().unwrap();
+/// assert_eq!(op, Transform::TileBe { axis: 0, tiles: vec![2, 3] });
+/// assert_eq!(op.to_string(), "tile_be(0, [2, 3])");
+///
+/// let layout = ArrayLayout::<4>::new(&[6], &[1], 0);
+/// let layout = op.apply(&layout).unwrap();
+/// assert_eq!(layout.shape(), &[2, 3]);
+/// assert_eq!(layout.strides(), &[3, 1]);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Transform {
+    /// 见 [`ArrayLayout::index`]。
+    Index {
+        /// 索引的轴。
+        axis: usize,
+        /// 下标。
+        index: usize,
+    },
+    /// 见 [`ArrayLayout::slice`]。
+    Slice {
+        /// 切片的轴。
+        axis: usize,
+        /// 起点。
+        start: usize,
+        /// 步长。
+        step: isize,
+        /// 长度。
+        len: usize,
+    },
+    /// 见 [`ArrayLayout::tile_be`]。
+    TileBe {
+        /// 分块的轴。
+        axis: usize,
+        /// 分块的形状。
+        tiles: Vec<usize>,
+    },
+    /// 见 [`ArrayLayout::tile_le`]。
+    TileLe {
+        /// 分块的轴。
+        axis: usize,
+        /// 分块的形状。
+        tiles: Vec<usize>,
+    },
+    /// 见 [`ArrayLayout::merge_be`]。
+    MergeBe {
+        /// 第一个合并的轴。
+        start: usize,
+        /// 合并的轴数。
+        len: usize,
+    },
+    /// 见 [`ArrayLayout::merge_le`]。
+    MergeLe {
+        /// 第一个合并的轴。
+        start: usize,
+        /// 合并的轴数。
+        len: usize,
+    },
+    /// 见 [`ArrayLayout::merge_free`]。
+    MergeFree {
+        /// 第一个合并的轴。
+        start: usize,
+        /// 合并的轴数。
+        len: usize,
+    },
+    /// 见 [`ArrayLayout::transpose`]。
+    Transpose {
+        /// 转置的排列。
+        perm: Vec<usize>,
+    },
+    /// 见 [`ArrayLayout::broadcast`]。
+    Broadcast {
+        /// 广播的轴。
+        axis: usize,
+        /// 广播后的长度。
+        times: usize,
+    },
+    /// 见 [`ArrayLayout::windows`]。
+    Windows {
+        /// 滑动窗口的轴。
+        axis: usize,
+        /// 窗口的大小。
+        size: usize,
+        /// 窗口滑动的步长。
+        step: usize,
+    },
+    /// [`Transform::Index`] 的逆变换，在 `axis` 处插入被索引掉的维度。
+    Unindex {
+        /// 插入的轴。
+        axis: usize,
+        /// 原来的下标。
+        index: usize,
+        /// 插入维度的长度。
+        len: usize,
+        /// 插入维度的步长。
+        stride: isize,
+    },
+    /// [`Transform::Slice`] 的逆变换，把切片的维度还原为原来的维度。
+    Unslice {
+        /// 切片的轴。
+        axis: usize,
+        /// 原来的起点。
+        start: usize,
+        /// 原来的步长，不能为 0。
+        step: isize,
+        /// 原来的长度。
+        len: usize,
+    },
+    /// [`Transform::Windows`] 的逆变换，移除最后的窗口阶，把 `axis` 还原为原来的维度。
+    Unwindows {
+        /// 滑动窗口的轴。
+        axis: usize,
+        /// 原来的长度。
+        len: usize,
+    },
+}
+
+impl Transform {
+    /// 对布局应用变换，参数与布局的形状不匹配或合并失败时返回 `None`。
+    #[inline]
+    pub fn apply<const N: usize>(&self, layout: &ArrayLayout<N>) -> Option<ArrayLayout<N>> {
+        let mut ans = layout.clone();
+        self.apply_inplace(&mut ans).then_some(ans)
+    }
+
+    /// 原地对布局应用变换，参数与布局的形状不匹配或合并失败时返回 `false`，布局不变。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, Endian::BigEndian, Transform};
+    /// let mut layout = ArrayLayout::<4>::new_contiguous(&[2, 3, 4], BigEndian, 4);
+    /// assert!(Transform::Index { axis: 1, index: 2 }.apply_inplace(&mut layout));
+    /// assert!(!Transform::MergeLe { start: 0, len: 2 }.apply_inplace(&mut layout));
+    /// assert_eq!(layout.shape(), &[2, 4]);
+    /// assert_eq!(layout.offset(), 32);
+    /// ```
+    pub fn apply_inplace<const N: usize>(&self, layout: &mut ArrayLayout<N>) -> bool {
+        if !self.fits(layout) {
+            return false;
+        }
+        match *self {
+            Self::Index { axis, index } => {
+                layout.index_inplace(axis, index);
+            }
+            Self::Slice {
+                axis,
+                start,
+                step,
+                len,
+            } => {
+                layout.slice_inplace(axis, start, step, len);
+            }
+            Self::TileBe { axis, ref tiles } => {
+                layout.tile_be_inplace(axis, tiles);
+            }
+            Self::TileLe { axis, ref tiles } => {
+                layout.tile_le_inplace(axis, tiles);
+            }
+            Self::MergeBe { start, len } => return layout.merge_be_inplace(start, len),
+            Self::MergeLe { start, len } => return layout.merge_le_inplace(start, len),
+            Self::MergeFree { start, len } => return layout.merge_free_inplace(start, len),
+            Self::Transpose { ref perm } => {
+                layout.transpose_inplace(perm);
+            }
+            Self::Broadcast { axis, times } => {
+                layout.broadcast_inplace(axis, times);
+            }
+            Self::Unindex {
+                axis,
+                index,
+                len,
+                stride,
+            } => {
+                assert!(index < len);
+                layout.splice_dims(axis..axis, 1);
+                let mut content = layout.content_mut();
+                content.set_shape(axis, len);
+                content.set_stride(axis, stride);
+                content.set_offset(content.offset() - index as isize * stride)
+            }
+            Self::Unslice {
+                axis,
+                start,
+                step,
+                len,
+            } => {
+                assert_ne!(step, 0);
+                let mut content = layout.content_mut();
+                let stride = content.strides()[axis] / step;
+                content.set_shape(axis, len);
+                content.set_stride(axis, stride);
+                content.set_offset(content.offset() - start as isize * stride)
+            }
+            Self::Windows { axis, size, step } => {
+                let ndim = layout.ndim();
+                layout.splice_dims(ndim..ndim, 1);
+                let mut content = layout.content_mut();
+                let d = content.shape()[axis];
+                let s = content.strides()[axis];
+                content.set_shape(axis, (d - size) / step + 1);
+                content.set_stride(axis, s * step as isize);
+                content.set_shape(ndim, size);
+                content.set_stride(ndim, s)
+            }
+            Self::Unwindows { axis, len } => {
+                let last = layout.ndim() - 1;
+                let s = layout.strides()[last];
+                layout.splice_dims(last..last + 1, 0);
+                let mut content = layout.content_mut();
+                content.set_shape(axis, len);
+                content.set_stride(axis, s)
+            }
+        }
+        true
+    }
+
+    /// 检查变换的参数是否适用于布局的形状，即对应的变换方法不会 panic。
+    fn fits<const N: usize>(&self, layout: &ArrayLayout<N>) -> bool {
+        let shape = layout.shape();
+        let ndim = shape.len();
+        match *self {
+            Self::Index { axis, index } => shape.get(axis).is_some_and(|&d| index < d),
+            Self::Slice {
+                axis, start, step, ..
+            } => shape
+                .get(axis)
+                .is_some_and(|&d| if step < 0 { d > 0 } else { start < d }),
+            Self::TileBe { axis, ref tiles } | Self::TileLe { axis, ref tiles } => shape
+                .get(axis)
+                .is_some_and(|&d| Some(d) == tiles.iter().copied().try_fold(1, usize::checked_mul)),
+            Self::MergeBe { start, len }
+            | Self::MergeLe { start, len }
+            | Self::MergeFree { start, len } => {
+                start.checked_add(len).is_some_and(|end| end <= ndim)
+            }
+            Self::Transpose { ref perm } => perm
+                .iter()
+                .enumerate()
+                .all(|(i, &p)| p < ndim && !perm[..i].contains(&p)),
+            Self::Broadcast { axis, .. } => {
+                axis < ndim && (shape[axis] == 1 || layout.strides()[axis] == 0)
+            }
+            Self::Unindex {
+                axis, index, len, ..
+            } => axis <= ndim && index < len,
+            Self::Unslice { axis, step, .. } => axis < ndim && step != 0,
+            Self::Windows { axis, size, step } => {
+                shape.get(axis).is_some_and(|&d| 0 < size && size <= d) && step > 0
+            }
+            Self::Unwindows { axis, .. } => axis + 1 < ndim,
+        }
+    }
+
+    /// 求变换的逆变换，`input` 是这个变换的输入布局。
+    ///
+    /// 逆变换作用于变换的输出，得到与 `input` 等价的布局，长度为 1 的维度的步长可能不同。
+    /// 步长为 0 的切片、不按大端或小端顺序合并的 [`Transform::MergeFree`]
+    /// 和输入不是滑动窗口的 [`Transform::Unwindows`] 不可逆，返回 `None`。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, Endian::BigEndian, Transform};
+    /// let input = ArrayLayout::<4>::new_contiguous(&[2, 3, 4], BigEndian, 4);
+    /// let op = Transform::Index { axis: 1, index: 2 };
+    /// let output = op.apply(&input).unwrap();
+    ///
+    /// let inverse = op.inverse(&input).unwrap();
+    /// assert_eq!(inverse, Transform::Unindex { axis: 1, index: 2, len: 3, stride: 16 });
+    /// assert!(inverse.apply(&output).unwrap() == input);
+    /// ```
+    pub fn inverse<const N: usize>(&self, input: &ArrayLayout<N>) -> Option<Self> {
+        let shape = input.shape();
+        let strides = input.strides();
+        Some(match *self {
+            Self::Index { axis, index } => Self::Unindex {
+                axis,
+                index,
+                len: shape[axis],
+                stride: strides[axis],
+            },
+            Self::Unindex { axis, index, .. } => Self::Index { axis, index },
+            Self::Slice {
+                axis, start, step, ..
+            } => {
+                if step == 0 {
+                    return None;
+                }
+                // 负步长的起点被截断到维度内
+                let start = if step < 0 {
+                    start.min(shape[axis] - 1)
+                } else {
+                    start
+                };
+                Self::Unslice {
+                    axis,
+                    start,
+                    step,
+                    len: shape[axis],
+                }
+            }
+            Self::Unslice {
+                axis, start, step, ..
+            } => Self::Slice {
+                axis,
+                start,
+                step,
+                len: shape[axis],
+            },
+            Self::TileBe { axis, ref tiles } | Self::TileLe { axis, ref tiles }
+                if tiles.is_empty() =>
+            {
+                Self::Unindex {
+                    axis,
+                    index: 0,
+                    len: 1,
+                    stride: strides[axis],
+                }
+            }
+            Self::TileBe { axis, ref tiles } => Self::MergeBe {
+                start: axis,
+                len: tiles.len(),
+            },
+            Self::TileLe { axis, ref tiles } => Self::MergeLe {
+                start: axis,
+                len: tiles.len(),
+            },
+            Self::MergeBe { start, len } if len > 0 => Self::TileBe {
+                axis: start,
+                tiles: shape[start..][..len].to_vec(),
+            },
+            Self::MergeLe { start, len } if len > 0 => Self::TileLe {
+                axis: start,
+                tiles: shape[start..][..len].to_vec(),
+            },
+            Self::MergeFree { start, len } if len > 0 => {
+                let tiles = shape[start..][..len].to_vec();
+                let axis = start;
+                if input.merge_be(start, len).is_some() {
+                    Self::TileBe { axis, tiles }
+                } else if input.merge_le(start, len).is_some() {
+                    Self::TileLe { axis, tiles }
+                } else {
+                    return None;
+                }
+            }
+            Self::MergeBe { .. } | Self::MergeLe { .. } | Self::MergeFree { .. } => return None,
+            Self::Transpose { ref perm } => Self::Transpose {
+                perm: ArrayLayout::<N>::invert_perm(perm),
+            },
+            Self::Broadcast { axis, .. } => match shape[axis] {
+                1 => Self::Slice {
+                    axis,
+                    start: 0,
+                    step: 1,
+                    len: 1,
+                },
+                times => Self::Broadcast { axis, times },
+            },
+            Self::Windows { axis, .. } => Self::Unwindows {
+                axis,
+                len: shape[axis],
+            },
+            Self::Unwindows { axis, len } => {
+                // 窗口阶的步长是原来的步长，窗口数量阶的步长是它的整数倍
+                let last = shape.len() - 1;
+                let (n, size, s) = (shape[axis], shape[last], strides[last]);
+                if size == 0 || size > len {
+                    return None;
+                }
+                let step = match n {
+                    1 => len - size + 1,
+                    _ if s != 0 && strides[axis] % s == 0 && strides[axis] / s > 0 => {
+                        (strides[axis] / s) as usize
+                    }
+                    _ => return None,
+                };
+                if (len - size) / step + 1 != n {
+                    return None;
+                }
+                Self::Windows { axis, size, step }
+            }
+        })
+    }
+
+    /// 求一串变换的逆，`base` 是第一个变换的输入布局。
+    ///
+    /// 返回的变换依次作用于最后一个变换的输出，得到与 `base` 等价的布局。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, Endian::BigEndian, Transform};
+    /// let base = ArrayLayout::<4>::new_contiguous(&[2, 3, 4], BigEndian, 4);
+    /// let ops = Transform::parse_recipe("transpose([2, 0]); merge_le(1, 2); index(0, 1)").unwrap();
+    /// let output = Transform::replay(&ops, &base).unwrap();
+    ///
+    /// let inverse = Transform::invert_chain(&ops, &base).unwrap();
+    /// assert_eq!(
+    ///     inverse.iter().map(|op| op.to_string()).collect::<Vec<_>>(),
+    ///     ["unindex(0, 1, 4, 4)", "tile_le(1, [3, 2])", "transpose([2, 0])"],
+    /// );
+    /// assert!(Transform::replay(&inverse, &output).unwrap() == base);
+    /// ```
+    pub fn invert_chain<const N: usize>(ops: &[Self], base: &ArrayLayout<N>) -> Option<Vec<Self>> {
+        let mut layout = base.clone();
+        let mut ans = Vec::with_capacity(ops.len());
+        for op in ops {
+            ans.push(op.inverse(&layout)?);
+            layout = op.apply(&layout)?
+        }
+        ans.reverse();
+        Some(ans)
+    }
+
+    /// 依次对布局应用一串变换，有变换与形状不匹配或合并失败时返回 `None`。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, Endian::BigEndian, Transform};
+    /// let ops = Transform::parse_recipe("tile_be(1, [32, 128])").unwrap();
+    /// let fit = ArrayLayout::<4>::new_contiguous(&[2, 4096], BigEndian, 2);
+    /// let small = ArrayLayout::<4>::new_contiguous(&[2, 1024], BigEndian, 2);
+    /// assert_eq!(Transform::replay(&ops, &fit).unwrap().shape(), &[2, 32, 128]);
+    /// assert!(Transform::replay(&ops, &small).is_none());
+    /// ```
+    pub fn replay<const N: usize>(ops: &[Self], base: &ArrayLayout<N>) -> Option<ArrayLayout<N>> {
+        ops.iter()
+            .try_fold(base.clone(), |layout, op| op.apply(&layout))
+    }
+
+    /// 解析以 `;` 分隔的一串变换，空白的项被忽略。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::Transform;
+    /// let ops = Transform::parse_recipe("transpose([1, 0]); merge_be(0, 2);").unwrap();
+    /// assert_eq!(ops, [
+    ///     Transform::Transpose { perm: vec![1, 0] },
+    ///     Transform::MergeBe { start: 0, len: 2 },
+    /// ]);
+    /// assert!(Transform::parse_recipe("flip(0)").is_err());
+    /// ```
+    pub fn parse_recipe(s: &str) -> Result<Vec<Self>, ParseTransformError> {
+        s.split(';')
+            .filter(|s| !s.trim().is_empty())
+            .map(str::parse)
+            .collect()
+    }
+}
+
+impl fmt::Display for Transform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Index { axis, index } => write!(f, "index({axis}, {index})"),
+            Self::Slice {
+                axis,
+                start,
+                step,
+                len,
+            } => write!(f, "slice({axis}, {start}, {step}, {len})"),
+            Self::TileBe { axis, tiles } => write!(f, "tile_be({axis}, {tiles:?})"),
+            Self::TileLe { axis, tiles } => write!(f, "tile_le({axis}, {tiles:?})"),
+            Self::MergeBe { start, len } => write!(f, "merge_be({start}, {len})"),
+            Self::MergeLe { start, len } => write!(f, "merge_le({start}, {len})"),
+            Self::MergeFree { start, len } => write!(f, "merge_free({start}, {len})"),
+            Self::Transpose { perm } => write!(f, "transpose({perm:?})"),
+            Self::Broadcast { axis, times } => write!(f, "broadcast({axis}, {times})"),
+            Self::Unindex {
+                axis,
+                index,
+                len,
+                stride,
+            } => write!(f, "unindex({axis}, {index}, {len}, {stride})"),
+            Self::Unslice {
+                axis,
+                start,
+                step,
+                len,
+            } => write!(f, "unslice({axis}, {start}, {step}, {len})"),
+            Self::Windows { axis, size, step } => write!(f, "windows({axis}, {size}, {step})"),
+            Self::Unwindows { axis, len } => write!(f, "unwindows({axis}, {len})"),
+        }
+    }
+}
+
+/// 解析变换的错误。
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ParseTransformError(String);
+
+impl fmt::Display for ParseTransformError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid transform `{}`", self.0)
+    }
+}
+
+impl Error for ParseTransformError {}
+
+/// 变换的参数。
+enum Arg {
+    Int(isize),
+    List(Vec<usize>),
+}
+
+impl FromStr for Transform {
+    type Err = ParseTransformError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseTransformError(s.trim().into());
+
+        let (name, args) = s.trim().split_once('(').ok_or_else(err)?;
+        let args = args.strip_suffix(')').ok_or_else(err)?;
+
+        // 逐个解析以逗号分隔的整数或方括号中的列表
+        let mut parsed = Vec::new();
+        let mut rest = args.trim();
+        while !rest.is_empty() {
+            let (arg, tail) = match rest.strip_prefix('[') {
+                Some(list) => {
+                    let (list, tail) = list.split_once(']').ok_or_else(err)?;
+                    let list = list
+                        .split(',')
+                        .filter(|s| !s.trim().is_empty())
+                        .map(|s| s.trim().parse().map_err(|_| err()))
+                        .collect::<Result<_, _>>()?;
+                    (Arg::List(list), tail)
+                }
+                None => {
+                    let (int, tail) = rest.split_at(rest.find(',').unwrap_or(rest.len()));
+                    (Arg::Int(int.trim().parse().map_err(|_| err())?), tail)
+                }
+            };
+            parsed.push(arg);
+            let tail = tail.trim();
+            rest = match tail.strip_prefix(',') {
+                Some(tail) => tail.trim(),
+                None if tail.is_empty() => tail,
+                None => return Err(err()),
+            };
+        }
+
+        let uint = |i: isize| usize::try_from(i).map_err(|_| err());
+        use Arg::*;
+        Ok(match (name.trim(), &*parsed) {
+            ("index", &[Int(axis), Int(index)]) => Self::Index {
+                axis: uint(axis)?,
+                index: uint(index)?,
+            },
+            ("slice", &[Int(axis), Int(start), Int(step), Int(len)]) => Self::Slice {
+                axis: uint(axis)?,
+                start: uint(start)?,
+                step,
+                len: uint(len)?,
+            },
+            ("tile_be", [Int(axis), List(tiles)]) => Self::TileBe {
+                axis: uint(*axis)?,
+                tiles: tiles.clone(),
+            },
+            ("tile_le", [Int(axis), List(tiles)]) => Self::TileLe {
+                axis: uint(*axis)?,
+                tiles: tiles.clone(),
+            },
+            ("merge_be", &[Int(start), Int(len)]) => Self::MergeBe {
+                start: uint(start)?,
+                len: uint(len)?,
+            },
+            ("merge_le", &[Int(start), Int(len)]) => Self::MergeLe {
+                start: uint(start)?,
+                len: uint(len)?,
+            },
+            ("merge_free", &[Int(start), Int(len)]) => Self::MergeFree {
+                start: uint(start)?,
+                len: uint(len)?,
+            },
+            ("transpose", [List(perm)]) => Self::Transpose { perm: perm.clone() },
+            ("broadcast", &[Int(axis), Int(times)]) => Self::Broadcast {
+                axis: uint(axis)?,
+                times: uint(times)?,
+            },
+            ("unindex", &[Int(axis), Int(index), Int(len), Int(stride)]) => Self::Unindex {
+                axis: uint(axis)?,
+                index: uint(index)?,
+                len: uint(len)?,
+                stride,
+            },
+            ("unslice", &[Int(axis), Int(start), Int(step), Int(len)]) => Self::Unslice {
+                axis: uint(axis)?,
+                start: uint(start)?,
+                step,
+                len: uint(len)?,
+            },
+            ("windows", &[Int(axis), Int(size), Int(step)]) => Self::Windows {
+                axis: uint(axis)?,
+                size: uint(size)?,
+                step: uint(step)?,
+            },
+            ("unwindows", &[Int(axis), Int(len)]) => Self::Unwindows {
+                axis: uint(axis)?,
+                len: uint(len)?,
+            },
+            _ => return Err(err()),
+        })
+    }
+}
+
+#[test]
+fn test() {
+    let ops = [
+        Transform::Index { axis: 1, index: 3 },
+        Transform::Slice {
+            axis: 0,
+            start: 2,
+            step: -1,
+            len: 4,
+        },
+        Transform::TileBe {
+            axis: 2,
+            tiles: vec![2, 3],
+        },
+        Transform::TileLe {
+            axis: 0,
+            tiles: vec![],
+        },
+        Transform::MergeBe { start: 0, len: 2 },
+        Transform::MergeLe { start: 1, len: 3 },
+        Transform::MergeFree { start: 0, len: 0 },
+        Transform::Transpose { perm: vec![2, 0] },
+        Transform::Broadcast { axis: 0, times: 5 },
+        Transform::Unindex {
+            axis: 1,
+            index: 2,
+            len: 3,
+            stride: -4,
+        },
+        Transform::Unslice {
+            axis: 0,
+            start: 1,
+            step: 2,
+            len: 7,
+        },
+        Transform::Windows {
+            axis: 1,
+            size: 3,
+            step: 2,
+        },
+        Transform::Unwindows { axis: 0, len: 9 },
+    ];
+    for op in ops {
+        assert_eq!(op.to_string().parse::<Transform>(), Ok(op.clone()));
+    }
+    assert_eq!(
+        " slice( 0 ,1,-2 , 3 ) ".parse::<Transform>(),
+        Ok(Transform::Slice {
+            axis: 0,
+            start: 1,
+            step: -2,
+            len: 3
+        })
+    );
+    for s in [
+        "index(1)",
+        "index(-1, 0)",
+        "transpose(1, 0)",
+        "tile_be(0, [2, x])",
+        "merge_be(0, 2",
+        "broadcast(0, 2) x",
+    ] {
+        assert_eq!(
+            s.parse::<Transform>(),
+            Err(ParseTransformError(s.into())),
+            "{s}"
+        );
+    }
+
+    // 逆变换把输出映射回与输入等价的布局
+    fn equivalent(a: &ArrayLayout<4>, b: &ArrayLayout<4>) -> bool {
+        let dims = |l: &ArrayLayout<4>| {
+            std::iter::zip(l.shape(), l.strides())
+                .map(|(&d, &s)| (d, if d == 1 { 0 } else { s }))
+                .collect::<Vec<_>>()
+        };
+        a.offset() == b.offset() && dims(a) == dims(b)
+    }
+
+    let base = ArrayLayout::<4>::new_contiguous(&[1, 6, 4, 5], crate::Endian::BigEndian, 2);
+    let recipe = "broadcast(0, 3); slice(3, 4, -2, 2); tile_le(1, [2, 3]); \
+        merge_free(1, 2); transpose([3, 0, 1]); index(1, 2); tile_be(0, [2, 1]); \
+        tile_be(1, []); slice(2, 1, 1, 3); merge_le(2, 0)";
+    let ops = Transform::parse_recipe(recipe).unwrap();
+    let ops = &ops[..ops.len() - 1];
+    let output = Transform::replay(ops, &base).unwrap();
+    let inverse = Transform::invert_chain(ops, &base).unwrap();
+    assert!(equivalent(
+        &Transform::replay(&inverse, &output).unwrap(),
+        &base
+    ));
+
+    // 不可逆的变换
+    let layout = ArrayLayout::<4>::new(&[2, 3, 4], &[4, 32, 8], 0);
+    let free = Transform::MergeFree { start: 0, len: 3 };
+    assert!(free.apply(&layout).is_some());
+    assert_eq!(free.inverse(&layout), None);
+    let slice = Transform::Slice {
+        axis: 0,
+        start: 1,
+        step: 0,
+        len: 3,
+    };
+    assert_eq!(slice.inverse(&layout), None);
+    assert_eq!(
+        Transform::MergeBe { start: 0, len: 0 }.inverse(&layout),
+        None
+    );
+    // 只有一个窗口时，逆变换选择恰好只产生一个窗口的步长
+    let layout = ArrayLayout::<4>::new_contiguous(&[5, 2], crate::Endian::BigEndian, 2);
+    let windows = Transform::Windows {
+        axis: 0,
+        size: 4,
+        step: 2,
+    };
+    let output = windows.apply(&layout).unwrap();
+    assert_eq!(output.shape(), &[1, 2, 4]);
+    let unwindows = windows.inverse(&layout).unwrap();
+    assert!(unwindows.apply(&output).unwrap() == layout);
+    assert!(equivalent(
+        &unwindows.inverse(&output).unwrap().apply(&layout).unwrap(),
+        &output
+    ));
+
+    // 与形状不匹配的变换不会 panic
+    let layout = ArrayLayout::<4>::new_contiguous(&[2, 1024], crate::Endian::BigEndian, 2);
+    for recipe in [
+        "index(2, 0)",
+        "index(0, 2)",
+        "slice(1, 1024, 1, 1)",
+        "slice(2, 0, -1, 1)",
+        "tile_be(1, [32, 128])",
+        "tile_le(2, [])",
+        "merge_be(1, 2)",
+        "merge_free(3, 0)",
+        "transpose([2, 0])",
+        "transpose([1, 1])",
+        "broadcast(0, 3)",
+        "unindex(3, 0, 2, 4)",
+        "unindex(0, 2, 2, 4)",
+        "unslice(0, 0, 0, 4)",
+        "windows(1, 1025, 1)",
+        "windows(0, 1, 0)",
+        "tile_be(0, [18446744073709551615, 2])",
+        "unwindows(1, 4)",
+    ] {
+        let ops = Transform::parse_recipe(recipe).unwrap();
+        assert!(Transform::replay(&ops, &layout).is_none(), "{recipe}");
+    }
+}
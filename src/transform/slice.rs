@@ -34,6 +34,45 @@ impl<const N: usize> ArrayLayout<N> {
         }])
     }
 
+    /// 原地进行切片变换，见 [`ArrayLayout::slice`]。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::ArrayLayout;
+    /// let mut layout = ArrayLayout::<3>::new(&[2, 3, 4], &[12, 4, 1], 0);
+    /// layout.slice_inplace(1, 2, -1, 2);
+    /// assert!(layout == ArrayLayout::new(&[2, 3, 4], &[12, 4, 1], 0).slice(1, 2, -1, 2));
+    /// ```
+    pub fn slice_inplace(
+        &mut self,
+        axis: usize,
+        start: usize,
+        step: isize,
+        len: usize,
+    ) -> &mut Self {
+        let mut content = self.content_mut();
+        let d = content.shape()[axis];
+        let s = content.strides()[axis];
+        use std::cmp::Ordering::*;
+        let (start, len) = match step.cmp(&0) {
+            Greater => {
+                assert!(start < d);
+                (start, (d - start).div_ceil(step as _).min(len))
+            }
+            Equal => {
+                assert!(start < d);
+                (start, len)
+            }
+            Less => {
+                let start = start.min(d - 1);
+                (start, (start + 1).div_ceil((-step) as _).min(len))
+            }
+        };
+        content.set_offset(content.offset() + start as isize * s);
+        content.set_shape(axis, len);
+        content.set_stride(axis, s * step);
+        self
+    }
+
     /// 一次对多个阶进行切片变换。
     pub fn slice_many(&self, mut args: &[SliceArg]) -> Self {
         let content = self.content();
@@ -51,6 +51,67 @@ impl<const N: usize> ArrayLayout<N> {
         }])
     }
 
+    /// 原地进行大端分块变换，见 [`ArrayLayout::tile_be`]。
+    ///
+    /// 升阶后仍能内联存储时复用内联空间，否则原地扩大堆上的空间。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::ArrayLayout;
+    /// let mut layout = ArrayLayout::<3>::new(&[2, 3, 6], &[18, 6, 1], 0);
+    /// layout.tile_be_inplace(2, &[2, 3]);
+    /// assert_eq!(layout.shape(), &[2, 3, 2, 3]);
+    /// assert_eq!(layout.strides(), &[18, 6, 3, 1]);
+    /// ```
+    #[inline]
+    pub fn tile_be_inplace(&mut self, axis: usize, tiles: &[usize]) -> &mut Self {
+        self.tile_inplace(axis, Endian::BigEndian, tiles)
+    }
+
+    /// 原地进行小端分块变换，见 [`ArrayLayout::tile_le`]。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::ArrayLayout;
+    /// let mut layout = ArrayLayout::<3>::new(&[2, 3, 6], &[18, 6, 1], 0);
+    /// layout.tile_le_inplace(2, &[2, 3]);
+    /// assert_eq!(layout.shape(), &[2, 3, 2, 3]);
+    /// assert_eq!(layout.strides(), &[18, 6, 1, 2]);
+    /// ```
+    #[inline]
+    pub fn tile_le_inplace(&mut self, axis: usize, tiles: &[usize]) -> &mut Self {
+        self.tile_inplace(axis, Endian::LittleEndian, tiles)
+    }
+
+    fn tile_inplace(&mut self, axis: usize, endian: Endian, tiles: &[usize]) -> &mut Self {
+        let content = self.content();
+        let d = content.shape().get(axis).copied();
+        assert_eq!(d, Some(tiles.iter().product()));
+        let s = content.strides()[axis];
+
+        self.splice_dims(axis..axis + 1, tiles.len());
+        let mut content = self.content_mut();
+        let mut set = |i, t, s| {
+            content.set_shape(axis + i, t);
+            content.set_stride(axis + i, s);
+        };
+        match endian {
+            Endian::BigEndian => {
+                let mut s = s * d.unwrap() as isize;
+                for (i, &t) in tiles.iter().enumerate() {
+                    s /= t as isize;
+                    set(i, t, s)
+                }
+            }
+            Endian::LittleEndian => {
+                let mut s = s;
+                for (i, &t) in tiles.iter().enumerate() {
+                    set(i, t, s);
+                    s *= t as isize
+                }
+            }
+        }
+        self
+    }
+
     /// 一次对多个阶进行分块变换。
     pub fn tile_many(&self, mut args: &[TileArg]) -> Self {
         let content = self.content();
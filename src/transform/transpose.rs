@@ -40,4 +40,70 @@ impl<const N: usize> ArrayLayout<N> {
         }
         ans
     }
+
+    /// 原地进行转置变换，见 [`ArrayLayout::transpose`]。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::ArrayLayout;
+    /// let mut layout = ArrayLayout::<3>::new(&[2, 3, 4], &[12, 4, 1], 0);
+    /// layout.transpose_inplace(&[2, 0]);
+    /// assert_eq!(layout.shape(), &[4, 3, 2]);
+    /// assert_eq!(layout.strides(), &[1, 4, 12]);
+    /// ```
+    pub fn transpose_inplace(&mut self, perm: &[usize]) -> &mut Self {
+        assert!(
+            perm.iter().enumerate().all(|(i, p)| !perm[..i].contains(p)),
+            "perm must not repeat axes"
+        );
+        // 第 k 小的位置放置第 perm[k] 维
+        let src = |i: usize| perm[perm.iter().filter(|&&j| j < i).count()];
+
+        let mut content = self.content_mut();
+        for &leader in perm {
+            // 每个置换环只从其中最小的位置开始轮换一次
+            let mut i = src(leader);
+            while i > leader {
+                i = src(i)
+            }
+            if i != leader {
+                continue;
+            }
+            let d = content.shape()[leader];
+            let s = content.strides()[leader];
+            let mut i = leader;
+            loop {
+                let j = src(i);
+                if j == leader {
+                    content.set_shape(i, d);
+                    content.set_stride(i, s);
+                    break;
+                }
+                content.copy_dim(j, i);
+                i = j
+            }
+        }
+        self
+    }
+
+    /// 求转置的逆排列，`perm` 与 [`ArrayLayout::transpose`] 的参数含义相同。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::ArrayLayout;
+    /// let layout = ArrayLayout::<4>::new(&[2, 3, 4, 5], &[60, 20, 5, 1], 0);
+    /// let perm = [3, 0, 1];
+    /// let inverse = ArrayLayout::<4>::invert_perm(&perm);
+    /// assert_eq!(inverse, [1, 3, 0]);
+    /// assert!(layout.transpose(&perm).transpose(&inverse) == layout);
+    /// ```
+    pub fn invert_perm(perm: &[usize]) -> Vec<usize> {
+        // 转置把 `perm[k]` 放到第 k 小的位置上，逆转置把它放回去
+        let mut sorted = perm.to_vec();
+        sorted.sort_unstable();
+        let mut ans = vec![0; perm.len()];
+        for (&src, &dst) in zip(perm, &sorted) {
+            let k = sorted.binary_search(&src).unwrap();
+            ans[k] = dst
+        }
+        ans
+    }
 }
@@ -0,0 +1,106 @@
+use crate::ArrayLayout;
+use std::iter::zip;
+
+/// 滑动窗口变换参数。
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct WindowArg {
+    /// 滑动窗口的轴。
+    pub axis: usize,
+    /// 窗口的大小。
+    pub size: usize,
+    /// 窗口滑动的步长。
+    pub step: usize,
+}
+
+impl<const N: usize> ArrayLayout<N> {
+    /// 滑动窗口变换将指定阶展开为多个可能重叠的窗口。
+    /// 原阶的长度变为窗口数量，步长乘以滑动步长；窗口内的元素构成新的一阶，添加在形状的末尾。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::ArrayLayout;
+    /// let layout = ArrayLayout::<3>::new(&[2, 10], &[10, 1], 0).windows(1, 3, 2);
+    /// assert_eq!(layout.shape(), &[2, 4, 3]);
+    /// assert_eq!(layout.strides(), &[10, 2, 1]);
+    /// assert_eq!(layout.offset(), 0);
+    /// assert!(layout.has_overlap());
+    /// ```
+    #[inline]
+    pub fn windows(&self, axis: usize, size: usize, step: usize) -> Self {
+        self.windows_many(&[WindowArg { axis, size, step }])
+    }
+
+    /// 一次对多个阶进行滑动窗口变换，窗口阶按参数的顺序添加在形状的末尾。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, WindowArg};
+    /// // 从 6x6 的图像中提取步长为 2 的 3x3 图块
+    /// let layout = ArrayLayout::<4>::new(&[6, 6], &[6, 1], 0).windows_many(&[
+    ///     WindowArg { axis: 0, size: 3, step: 2 },
+    ///     WindowArg { axis: 1, size: 3, step: 2 },
+    /// ]);
+    /// assert_eq!(layout.shape(), &[2, 2, 3, 3]);
+    /// assert_eq!(layout.strides(), &[12, 2, 6, 1]);
+    /// ```
+    pub fn windows_many(&self, args: &[WindowArg]) -> Self {
+        let mut ans = Self::with_ndim(self.ndim + args.len());
+        let mut content = ans.content_mut();
+        content.set_offset(self.offset());
+        for (i, (&d, &s)) in zip(self.shape(), self.strides()).enumerate() {
+            content.set_shape(i, d);
+            content.set_stride(i, s);
+        }
+
+        for (i, &WindowArg { axis, size, step }) in args.iter().enumerate() {
+            let d = content.shape()[axis];
+            let s = content.strides()[axis];
+            assert!(
+                0 < size && size <= d && step > 0,
+                "Invalid window arg: {:?}",
+                args[i]
+            );
+            content.set_shape(axis, (d - size) / step + 1);
+            content.set_stride(axis, s * step as isize);
+            content.set_shape(self.ndim + i, size);
+            content.set_stride(self.ndim + i, s);
+        }
+        ans
+    }
+}
+
+#[test]
+fn test() {
+    use std::fmt;
+
+    struct Tensor(ArrayLayout<3>);
+
+    impl fmt::Display for Tensor {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            const DATA: [u8; 10] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+            unsafe { self.0.write_array(f, DATA.as_ptr()) }
+        }
+    }
+
+    let layout = ArrayLayout::<3>::new(&[10], &[1], 0);
+
+    let overlapped = layout.windows(0, 4, 3);
+    assert_eq!(overlapped.shape(), &[3, 4]);
+    assert!(overlapped.has_overlap());
+    assert_eq!(
+        Tensor(overlapped).to_string(),
+        "\
+array<3x4>[..]
+0 1 2 3 \n\
+3 4 5 6 \n\
+6 7 8 9 \n"
+    );
+
+    // 步长不小于窗口大小时没有重叠
+    let disjoint = layout.windows(0, 3, 3);
+    assert_eq!(disjoint.shape(), &[3, 3]);
+    assert!(!disjoint.has_overlap());
+
+    // 在窗口阶上再次展开窗口
+    let layout = layout.windows(0, 5, 1).windows(1, 2, 2);
+    assert_eq!(layout.shape(), &[6, 2, 2]);
+    assert_eq!(layout.strides(), &[1, 2, 1]);
+}
@@ -0,0 +1,539 @@
+use crate::{
+    ArrayLayout, BroadcastArg, Endian::BigEndian, IndexArg, MergeArg, SliceArg, TileArg, WindowArg,
+};
+use std::{error::Error, fmt, iter::zip, marker::PhantomData, ops::RangeInclusive, ptr::NonNull};
+
+/// 创建张量视图的错误。
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ViewError {
+    /// 布局访问的字节范围超出了数据。
+    OutOfBounds {
+        /// 布局访问的元素起始字节范围。
+        range: RangeInclusive<isize>,
+        /// 数据的字节数。
+        len: usize,
+    },
+    /// 布局中不同元素的位置重叠，无法切分为互不相交的可变视图。
+    Overlap,
+    /// 偏移或步长不是元素大小的整数倍。
+    Misaligned {
+        /// 不对齐的偏移或步长。
+        value: isize,
+        /// 元素大小。
+        element_size: usize,
+    },
+}
+
+impl fmt::Display for ViewError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfBounds { range, len } => {
+                write!(f, "range {range:?} out of {len} bytes")
+            }
+            Self::Misaligned {
+                value,
+                element_size,
+            } => write!(
+                f,
+                "{value} is not a multiple of element size {element_size}"
+            ),
+            Self::Overlap => write!(f, "layout is self-overlapping"),
+        }
+    }
+}
+
+impl Error for ViewError {}
+
+/// 借用切片的只读张量视图，布局的偏移和步长以字节为单位。
+///
+/// ```rust
+/// # use ndarray_layout::{ArrayLayout, Endian::BigEndian, TensorView};
+/// let data = (0..6).collect::<Vec<u32>>();
+/// let layout = ArrayLayout::<4>::new_contiguous(&[2, 3], BigEndian, 4);
+/// let view = TensorView::new(layout, &data).unwrap().transpose(&[1, 0]);
+/// assert_eq!(view.get(&[2, 1]), Some(&5));
+/// assert_eq!(view.iter().copied().collect::<Vec<_>>(), [0, 3, 1, 4, 2, 5]);
+/// assert_eq!(
+///     view.to_string(),
+///     "array<3x2>[..]\n0 3 \n1 4 \n2 5 \n",
+/// );
+/// ```
+pub struct TensorView<'a, T, const N: usize> {
+    layout: ArrayLayout<N>,
+    ptr: NonNull<T>,
+    len: usize,
+    _phantom: PhantomData<&'a [T]>,
+}
+
+/// 借用可变切片的张量视图，布局的偏移和步长以字节为单位。
+///
+/// ```rust
+/// # use ndarray_layout::{ArrayLayout, Endian::BigEndian, TensorViewMut};
+/// let mut data = [0u32; 6];
+/// let layout = ArrayLayout::<4>::new_contiguous(&[2, 3], BigEndian, 4);
+/// let mut view = TensorViewMut::new(layout, &mut data).unwrap().index(0, 1);
+/// *view.get_mut(&[2]).unwrap() = 7;
+/// assert_eq!(data, [0, 0, 0, 0, 0, 7]);
+/// ```
+pub struct TensorViewMut<'a, T, const N: usize> {
+    layout: ArrayLayout<N>,
+    ptr: NonNull<T>,
+    /// 独占借用的数据长度，切分出的部分不独占整个切片，为 0。
+    len: usize,
+    _phantom: PhantomData<&'a mut [T]>,
+}
+
+// 可变视图之间不共享元素，因此线程安全性与对应的引用相同
+unsafe impl<T: Sync, const N: usize> Send for TensorView<'_, T, N> {}
+unsafe impl<T: Sync, const N: usize> Sync for TensorView<'_, T, N> {}
+unsafe impl<T: Send, const N: usize> Send for TensorViewMut<'_, T, N> {}
+unsafe impl<T: Sync, const N: usize> Sync for TensorViewMut<'_, T, N> {}
+
+impl<T, const N: usize> Clone for TensorView<'_, T, N> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            layout: self.layout.clone(),
+            ptr: self.ptr,
+            len: self.len,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// 检查布局访问的所有元素都在数据范围内且与元素对齐。
+fn check<T, const N: usize>(layout: &ArrayLayout<N>, data: &[T]) -> Result<(), ViewError> {
+    if layout.num_elements() == 0 {
+        return Ok(());
+    }
+    let element_size = size_of::<T>();
+    let align = element_size.max(1) as isize;
+    let strides = zip(layout.shape(), layout.strides()).filter(|(d, _)| **d > 1);
+    for value in [layout.offset()]
+        .into_iter()
+        .chain(strides.map(|(_, &s)| s))
+    {
+        if value % align != 0 {
+            return Err(ViewError::Misaligned {
+                value,
+                element_size,
+            });
+        }
+    }
+
+    let range = layout.data_range();
+    let len = size_of_val(data);
+    if *range.start() < 0 || *range.end() + element_size as isize > len as isize {
+        return Err(ViewError::OutOfBounds { range, len });
+    }
+    Ok(())
+}
+
+/// 计算下标处元素的字节偏移，下标越界时返回 `None`。
+fn offset_of<const N: usize>(layout: &ArrayLayout<N>, index: &[usize]) -> Option<isize> {
+    if index.len() != layout.ndim() || zip(index, layout.shape()).any(|(i, d)| i >= d) {
+        return None;
+    }
+    Some(
+        layout.offset()
+            + zip(index, layout.strides())
+                .map(|(&i, &s)| i as isize * s)
+                .sum::<isize>(),
+    )
+}
+
+/// 为视图生成与 [`ArrayLayout`] 同名的变换方法。
+macro_rules! transforms {
+    ([$($ref:tt)*] $this:ident) => {
+        fn map($($ref)* $this, f: impl FnOnce(&ArrayLayout<N>) -> ArrayLayout<N>) -> Self {
+            Self {
+                layout: f(&$this.layout),
+                ptr: $this.ptr,
+                len: $this.len,
+                _phantom: PhantomData,
+            }
+        }
+
+        fn try_map(
+            $($ref)* $this,
+            f: impl FnOnce(&ArrayLayout<N>) -> Option<ArrayLayout<N>>,
+        ) -> Option<Self> {
+            Some(Self {
+                layout: f(&$this.layout)?,
+                ptr: $this.ptr,
+                len: $this.len,
+                _phantom: PhantomData,
+            })
+        }
+
+        /// 见 [`ArrayLayout::index`]。
+        pub fn index($($ref)* $this, axis: usize, index: usize) -> Self {
+            $this.map(|l| l.index(axis, index))
+        }
+
+        /// 见 [`ArrayLayout::index_many`]。
+        pub fn index_many($($ref)* $this, args: &[IndexArg]) -> Self {
+            $this.map(|l| l.index_many(args))
+        }
+
+        /// 见 [`ArrayLayout::slice`]。
+        pub fn slice($($ref)* $this, axis: usize, start: usize, step: isize, len: usize) -> Self {
+            $this.map(|l| l.slice(axis, start, step, len))
+        }
+
+        /// 见 [`ArrayLayout::slice_many`]。
+        pub fn slice_many($($ref)* $this, args: &[SliceArg]) -> Self {
+            $this.map(|l| l.slice_many(args))
+        }
+
+        /// 见 [`ArrayLayout::tile_be`]。
+        pub fn tile_be($($ref)* $this, axis: usize, tiles: &[usize]) -> Self {
+            $this.map(|l| l.tile_be(axis, tiles))
+        }
+
+        /// 见 [`ArrayLayout::tile_le`]。
+        pub fn tile_le($($ref)* $this, axis: usize, tiles: &[usize]) -> Self {
+            $this.map(|l| l.tile_le(axis, tiles))
+        }
+
+        /// 见 [`ArrayLayout::tile_many`]。
+        pub fn tile_many($($ref)* $this, args: &[TileArg]) -> Self {
+            $this.map(|l| l.tile_many(args))
+        }
+
+        /// 见 [`ArrayLayout::merge_be`]。
+        pub fn merge_be($($ref)* $this, start: usize, len: usize) -> Option<Self> {
+            $this.try_map(|l| l.merge_be(start, len))
+        }
+
+        /// 见 [`ArrayLayout::merge_le`]。
+        pub fn merge_le($($ref)* $this, start: usize, len: usize) -> Option<Self> {
+            $this.try_map(|l| l.merge_le(start, len))
+        }
+
+        /// 见 [`ArrayLayout::merge_free`]。
+        pub fn merge_free($($ref)* $this, start: usize, len: usize) -> Option<Self> {
+            $this.try_map(|l| l.merge_free(start, len))
+        }
+
+        /// 见 [`ArrayLayout::merge_many`]。
+        pub fn merge_many($($ref)* $this, args: &[MergeArg]) -> Option<Self> {
+            $this.try_map(|l| l.merge_many(args))
+        }
+
+        /// 见 [`ArrayLayout::transpose`]。
+        pub fn transpose($($ref)* $this, perm: &[usize]) -> Self {
+            $this.map(|l| l.transpose(perm))
+        }
+
+        /// 见 [`ArrayLayout::broadcast`]。
+        pub fn broadcast($($ref)* $this, axis: usize, times: usize) -> Self {
+            $this.map(|l| l.broadcast(axis, times))
+        }
+
+        /// 见 [`ArrayLayout::broadcast_many`]。
+        pub fn broadcast_many($($ref)* $this, args: &[BroadcastArg]) -> Self {
+            $this.map(|l| l.broadcast_many(args))
+        }
+
+        /// 见 [`ArrayLayout::windows`]。
+        pub fn windows($($ref)* $this, axis: usize, size: usize, step: usize) -> Self {
+            $this.map(|l| l.windows(axis, size, step))
+        }
+
+        /// 见 [`ArrayLayout::windows_many`]。
+        pub fn windows_many($($ref)* $this, args: &[WindowArg]) -> Self {
+            $this.map(|l| l.windows_many(args))
+        }
+    };
+}
+
+impl<'a, T, const N: usize> TensorView<'a, T, N> {
+    /// 检查布局访问的元素都在 `data` 内并且与元素对齐，然后创建视图。
+    pub fn new(layout: ArrayLayout<N>, data: &'a [T]) -> Result<Self, ViewError> {
+        check(&layout, data)?;
+        Ok(Self {
+            layout,
+            ptr: NonNull::from(data).cast(),
+            len: data.len(),
+            _phantom: PhantomData,
+        })
+    }
+
+    /// 视图的布局。
+    #[inline]
+    pub fn layout(&self) -> &ArrayLayout<N> {
+        &self.layout
+    }
+
+    /// 视图借用的数据。
+    ///
+    /// 从 [`TensorViewMut::split_mut`] 切分出的部分不独占整个切片，其只读视图返回空切片。
+    #[inline]
+    pub fn data(&self) -> &'a [T] {
+        // SAFETY: 指针和长度来自创建视图时借用的切片
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// 获取下标处的元素，下标越界时返回 `None`。
+    pub fn get(&self, index: &[usize]) -> Option<&'a T> {
+        let offset = offset_of(&self.layout, index)?;
+        // SAFETY: 创建视图时已检查所有元素都在数据内且对齐
+        Some(unsafe { self.ptr.byte_offset(offset).as_ref() })
+    }
+
+    /// 按大端顺序遍历所有元素。
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> + '_ {
+        (0..self.layout.num_elements()).map(move |i| {
+            let offset = self.layout.element_offset(i, BigEndian);
+            // SAFETY: 创建视图时已检查所有元素都在数据内且对齐
+            unsafe { self.ptr.byte_offset(offset).as_ref() }
+        })
+    }
+
+    /// 见 [`ArrayLayout::split`]。
+    pub fn split<'b>(
+        &'b self,
+        axis: usize,
+        parts: &'b [usize],
+    ) -> impl Iterator<Item = TensorView<'a, T, N>> + 'b {
+        let (ptr, len) = (self.ptr, self.len);
+        self.layout
+            .split(axis, parts)
+            .map(move |layout| TensorView {
+                layout,
+                ptr,
+                len,
+                _phantom: PhantomData,
+            })
+    }
+
+    transforms!([&] self);
+}
+
+impl<'a, T, const N: usize> TensorViewMut<'a, T, N> {
+    /// 检查布局访问的元素都在 `data` 内并且与元素对齐，然后创建视图。
+    pub fn new(layout: ArrayLayout<N>, data: &'a mut [T]) -> Result<Self, ViewError> {
+        check(&layout, data)?;
+        Ok(Self {
+            layout,
+            len: data.len(),
+            ptr: NonNull::from(data).cast(),
+            _phantom: PhantomData,
+        })
+    }
+
+    /// 视图的布局。
+    #[inline]
+    pub fn layout(&self) -> &ArrayLayout<N> {
+        &self.layout
+    }
+
+    /// 以只读视图借用。
+    #[inline]
+    pub fn as_view(&self) -> TensorView<'_, T, N> {
+        TensorView {
+            layout: self.layout.clone(),
+            ptr: self.ptr,
+            len: self.len,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// 获取下标处的元素，下标越界时返回 `None`。
+    pub fn get(&self, index: &[usize]) -> Option<&T> {
+        let offset = offset_of(&self.layout, index)?;
+        // SAFETY: 创建视图时已检查所有元素都在数据内且对齐
+        Some(unsafe { self.ptr.byte_offset(offset).as_ref() })
+    }
+
+    /// 获取下标处元素的可变引用，下标越界时返回 `None`。
+    pub fn get_mut(&mut self, index: &[usize]) -> Option<&mut T> {
+        let offset = offset_of(&self.layout, index)?;
+        // SAFETY: 创建视图时已检查所有元素都在数据内且对齐
+        Some(unsafe { self.ptr.byte_offset(offset).as_mut() })
+    }
+
+    /// 沿 `axis` 把视图切分为若干互不相交的可变视图，每份的长度由 `parts` 给出。
+    ///
+    /// 布局自身重叠时，不同的子视图可能写同一个元素，因此返回 [`ViewError::Overlap`]。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, Endian::BigEndian, TensorViewMut, ViewError};
+    /// let mut data = [0u32; 4];
+    /// let layout = ArrayLayout::<4>::new(&[4, 3], &[4, 0], 0);
+    /// let view = TensorViewMut::new(layout, &mut data).unwrap();
+    /// assert_eq!(view.split_mut(1, &[1, 2]).err(), Some(ViewError::Overlap));
+    ///
+    /// let layout = ArrayLayout::<4>::new_contiguous(&[4], BigEndian, 4);
+    /// let view = TensorViewMut::new(layout, &mut data).unwrap();
+    /// let [mut a, mut b] = <[_; 2]>::try_from(view.split_mut(0, &[1, 3]).unwrap()).ok().unwrap();
+    /// *a.get_mut(&[0]).unwrap() = 1;
+    /// *b.get_mut(&[2]).unwrap() = 2;
+    /// assert_eq!(data, [1, 0, 0, 2]);
+    /// ```
+    pub fn split_mut(self, axis: usize, parts: &[usize]) -> Result<Vec<Self>, ViewError> {
+        assert_eq!(self.layout.shape()[axis], parts.iter().sum());
+        if self.layout.has_overlap() {
+            return Err(ViewError::Overlap);
+        }
+        let mut start = 0;
+        Ok(parts
+            .iter()
+            .map(|&len| {
+                let part = self.part(axis, start, len);
+                start += len;
+                part
+            })
+            .collect())
+    }
+
+    /// 沿 `axis` 把视图切分为长度为 `size` 的可变视图，最后一份可能更短。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, Endian::BigEndian, TensorViewMut};
+    /// let mut data = [0u32; 12];
+    /// let layout = ArrayLayout::<4>::new_contiguous(&[3, 4], BigEndian, 4);
+    /// let view = TensorViewMut::new(layout, &mut data).unwrap();
+    /// std::thread::scope(|s| {
+    ///     for (i, mut part) in view.chunks_mut(1, 3).unwrap().into_iter().enumerate() {
+    ///         s.spawn(move || {
+    ///             let cols = part.layout().shape()[1];
+    ///             for r in 0..3 {
+    ///                 for c in 0..cols {
+    ///                     *part.get_mut(&[r, c]).unwrap() = i as u32 + 1
+    ///                 }
+    ///             }
+    ///         });
+    ///     }
+    /// });
+    /// assert_eq!(data, [1, 1, 1, 2, 1, 1, 1, 2, 1, 1, 1, 2]);
+    /// ```
+    pub fn chunks_mut(self, axis: usize, size: usize) -> Result<Vec<Self>, ViewError> {
+        assert_ne!(size, 0);
+        let d = self.layout.shape()[axis];
+        let mut parts = vec![size; d / size];
+        match d % size {
+            0 => {}
+            rem => parts.push(rem),
+        }
+        self.split_mut(axis, &parts)
+    }
+
+    /// 在 `axis` 上的下标 `mid` 处把视图切分为两个可变视图。
+    pub fn split_at_mut(self, axis: usize, mid: usize) -> Result<(Self, Self), ViewError> {
+        let d = self.layout.shape()[axis];
+        assert!(mid <= d);
+        let mut parts = self.split_mut(axis, &[mid, d - mid])?.into_iter();
+        Ok((parts.next().unwrap(), parts.next().unwrap()))
+    }
+
+    /// 沿 `axis` 从 `start` 开始取 `len` 个元素的子视图。
+    fn part(&self, axis: usize, start: usize, len: usize) -> Self {
+        // 切片要求起点在维度内，空的部分从 0 开始
+        let start = if len == 0 { 0 } else { start };
+        Self {
+            layout: self.layout.slice(axis, start, 1, len),
+            ptr: self.ptr,
+            len: 0,
+            _phantom: PhantomData,
+        }
+    }
+
+    transforms!([] self);
+}
+
+impl<T: fmt::Display + Copy, const N: usize> fmt::Display for TensorView<'_, T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // SAFETY: 创建视图时已检查所有元素都在数据内且对齐
+        unsafe { self.layout.write_array(f, self.ptr.as_ptr()) }
+    }
+}
+
+impl<T: fmt::Display + Copy, const N: usize> fmt::Display for TensorViewMut<'_, T, N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_view().fmt(f)
+    }
+}
+
+#[test]
+fn test() {
+    let data = (0..24).collect::<Vec<u16>>();
+    let layout = ArrayLayout::<4>::new_contiguous(&[2, 3, 4], BigEndian, 2);
+    let view = TensorView::new(layout.clone(), &data).unwrap();
+
+    let tiled = view.tile_be(2, &[2, 2]).merge_be(0, 2).unwrap();
+    assert_eq!(tiled.layout().shape(), &[6, 2, 2]);
+    assert_eq!(tiled.get(&[5, 1, 0]), Some(&22));
+    assert_eq!(tiled.get(&[6, 0, 0]), None);
+    assert_eq!(tiled.get(&[0, 0]), None);
+    assert!(view.transpose(&[2, 1]).merge_be(1, 2).is_none());
+
+    let parts = view.split(1, &[1, 2]).collect::<Vec<_>>();
+    assert_eq!(parts[1].iter().next(), Some(&4));
+    assert_eq!(parts[1].data(), &data[..]);
+    let windows = view.index(0, 1).windows(1, 2, 2);
+    assert_eq!(
+        windows.iter().copied().collect::<Vec<_>>(),
+        [12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23]
+    );
+    let broadcast = view.slice(0, 1, 1, 1).broadcast(0, 3);
+    assert_eq!(broadcast.get(&[2, 0, 0]), Some(&12));
+
+    // 越界和不对齐
+    assert_eq!(
+        TensorView::new(layout.clone(), &data[..23]).err(),
+        Some(ViewError::OutOfBounds {
+            range: 0..=46,
+            len: 46
+        })
+    );
+    let shifted = ArrayLayout::<4>::new(&[2, 3, 4], &[24, 8, 2], -2);
+    assert!(matches!(
+        TensorView::new(shifted, &data),
+        Err(ViewError::OutOfBounds { .. })
+    ));
+    let misaligned = ArrayLayout::<4>::new(&[2, 3], &[6, 3], 0);
+    assert_eq!(
+        TensorView::new(misaligned, &data).err(),
+        Some(ViewError::Misaligned {
+            value: 3,
+            element_size: 2
+        })
+    );
+    let empty = ArrayLayout::<4>::new(&[0, 3], &[7, 3], 1);
+    assert!(TensorView::new(empty, &data[..0]).is_ok());
+
+    let mut buf = data.clone();
+    let mut view = TensorViewMut::new(layout, &mut buf)
+        .unwrap()
+        .transpose(&[2, 0]);
+    *view.get_mut(&[3, 1, 1]).unwrap() = 100;
+    assert_eq!(view.get(&[3, 1, 1]), Some(&100));
+    assert_eq!(view.to_string().lines().next(), Some("array<4x3x2>[0, ..]"));
+    assert_eq!(buf[19], 100);
+
+    // 切分为不相交的可变视图
+    let layout = ArrayLayout::<4>::new_contiguous(&[2, 3], BigEndian, 2);
+    let view = TensorViewMut::new(layout.clone(), &mut buf).unwrap();
+    let (mut left, mut right) = view.transpose(&[1, 0]).split_at_mut(0, 3).unwrap();
+    assert_eq!(right.layout().shape(), &[0, 2]);
+    assert!(right.get_mut(&[0, 0]).is_none());
+    *left.get_mut(&[2, 1]).unwrap() = 200;
+    assert_eq!(buf[5], 200);
+
+    let view = TensorViewMut::new(layout.clone(), &mut buf).unwrap();
+    assert_eq!(view.as_view().data().len(), 24);
+    let parts = view.chunks_mut(0, 1).unwrap();
+    assert_eq!(parts.len(), 2);
+    assert_eq!(parts[1].get(&[0, 0]), Some(&3));
+    assert!(parts[1].as_view().data().is_empty());
+
+    let view = TensorViewMut::new(layout.index(0, 0), &mut buf).unwrap();
+    let windows = view.windows(0, 2, 1);
+    assert_eq!(
+        windows.split_mut(0, &[1, 1]).err(),
+        Some(ViewError::Overlap)
+    );
+}
@@ -0,0 +1,280 @@
+use crate::{ArrayLayout, Endian::BigEndian};
+use std::fmt::Write;
+
+/// 可视化中每个单元格的边长（像素）。
+const CELL: usize = 32;
+/// 内存图中每行的字节数。
+const BYTES_PER_LINE: usize = 32;
+
+impl<const N: usize> ArrayLayout<N> {
+    /// 以 ASCII 表格画出每个元素的偏移。
+    ///
+    /// 最后两个维度构成表格的行和列，更高的维度逐个展开，每个表格前标出高维的下标。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, Endian::BigEndian};
+    /// let layout = ArrayLayout::<4>::new_contiguous(&[2, 3], BigEndian, 4).transpose(&[1, 0]);
+    /// assert_eq!(
+    ///     layout.render_ascii(),
+    ///     "\
+    /// +---+----+
+    /// | 0 | 12 |
+    /// +---+----+
+    /// | 4 | 16 |
+    /// +---+----+
+    /// | 8 | 20 |
+    /// +---+----+
+    /// ",
+    /// );
+    /// ```
+    pub fn render_ascii(&self) -> String {
+        let shape = self.shape();
+        let (batch, [rows, cols]) = match *shape {
+            [] => (&[][..], [1, 1]),
+            [n] => (&[][..], [1, n]),
+            [ref batch @ .., r, c] => (batch, [r, c]),
+        };
+        let strides = self.strides();
+        let (sr, sc) = match *strides {
+            [] => (0, 0),
+            [s] => (0, s),
+            [.., r, c] => (r, c),
+        };
+
+        let mut ans = String::new();
+        let batch_size = batch.iter().product::<usize>();
+        for b in 0..batch_size {
+            // 高维下标和偏移
+            let mut rem = b;
+            let mut index = vec![0; batch.len()];
+            let mut base = self.offset();
+            for (i, &d) in batch.iter().enumerate().rev() {
+                index[i] = rem % d;
+                rem /= d;
+                base += index[i] as isize * strides[i]
+            }
+            if !batch.is_empty() {
+                let index = index.iter().map(|i| format!("{i}, ")).collect::<String>();
+                writeln!(ans, "[{index}.., ..]").unwrap();
+            }
+
+            let cells = (0..rows)
+                .map(|r| {
+                    (0..cols)
+                        .map(|c| (base + r as isize * sr + c as isize * sc).to_string())
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            let widths = (0..cols)
+                .map(|c| cells.iter().map(|row| row[c].len()).max().unwrap_or(0))
+                .collect::<Vec<_>>();
+            let border = widths
+                .iter()
+                .map(|w| format!("+{}", "-".repeat(w + 2)))
+                .collect::<String>()
+                + "+\n";
+
+            ans.push_str(&border);
+            for row in cells {
+                for (cell, w) in row.iter().zip(&widths) {
+                    write!(ans, "| {cell:>w$} ").unwrap()
+                }
+                ans.push_str("|\n");
+                ans.push_str(&border)
+            }
+        }
+        ans
+    }
+
+    /// 以 SVG 画出逻辑下标空间和线性内存。
+    ///
+    /// 左侧是逻辑网格，除最后一维外的维度展开为行；右侧是 `data_range` 覆盖的内存，
+    /// 每格一个元素大小。同一元素在两侧颜色相同，并用箭头相连。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, Endian::BigEndian};
+    /// let layout = ArrayLayout::<4>::new_contiguous(&[2, 2], BigEndian, 4).transpose(&[1, 0]);
+    /// let svg = layout.render_svg(4);
+    /// assert!(svg.starts_with("<svg"));
+    /// assert_eq!(svg.matches("<line").count(), 4);
+    /// assert_eq!(svg, layout.render_svg(4));
+    /// ```
+    pub fn render_svg(&self, element_size: usize) -> String {
+        let es = element_size.max(1) as isize;
+        let n = self.num_elements();
+        let cols = self.shape().last().copied().unwrap_or(1).max(1);
+        let rows = n.div_ceil(cols).max(1);
+
+        let (start, slots) = if n == 0 {
+            (0, 0)
+        } else {
+            let range = self.data_range();
+            let start = range.start().div_euclid(es);
+            (start, (range.end().div_euclid(es) - start + 1) as usize)
+        };
+
+        let mem_x = cols * CELL + 3 * CELL;
+        let width = mem_x + CELL + 2 * CELL;
+        let height = (rows.max(slots) + 2) * CELL;
+        let color = |i: usize| format!("hsl({}, 70%, 75%)", i * 360 / n.max(1));
+
+        let mut ans = String::new();
+        writeln!(
+            ans,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" font-family="monospace" font-size="10" text-anchor="middle">"#
+        )
+        .unwrap();
+        ans.push_str(
+            r#"<defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="6" markerHeight="6" orient="auto"><path d="M0,0 L10,5 L0,10 z"/></marker></defs>
+"#,
+        );
+        writeln!(
+            ans,
+            r#"<text x="{}" y="{}">index</text>"#,
+            CELL + cols * CELL / 2,
+            CELL / 2
+        )
+        .unwrap();
+        writeln!(
+            ans,
+            r#"<text x="{}" y="{}">memory</text>"#,
+            mem_x + CELL / 2,
+            CELL / 2
+        )
+        .unwrap();
+
+        // 内存格，被多个元素访问的格子使用最后一个元素的颜色
+        let mut owners = vec![None; slots];
+        for i in 0..n {
+            let slot = self.element_offset(i, BigEndian).div_euclid(es) - start;
+            owners[slot as usize] = Some(i)
+        }
+        for (k, owner) in owners.iter().enumerate() {
+            let fill = owner.map_or_else(|| "white".into(), color);
+            let y = CELL + k * CELL;
+            writeln!(
+                ans,
+                r#"<rect x="{mem_x}" y="{y}" width="{CELL}" height="{CELL}" fill="{fill}" stroke="black"/><text x="{}" y="{}">{}</text>"#,
+                mem_x + CELL / 2,
+                y + CELL / 2 + 4,
+                (start + k as isize) * es,
+            )
+            .unwrap()
+        }
+
+        // 逻辑格和箭头
+        for i in 0..n {
+            let (x, y) = (CELL + i % cols * CELL, CELL + i / cols * CELL);
+            let slot = (self.element_offset(i, BigEndian).div_euclid(es) - start) as usize;
+            writeln!(
+                ans,
+                r#"<rect x="{x}" y="{y}" width="{CELL}" height="{CELL}" fill="{}" stroke="black"/><text x="{}" y="{}">{i}</text>"#,
+                color(i),
+                x + CELL / 2,
+                y + CELL / 2 + 4,
+            )
+            .unwrap();
+            writeln!(
+                ans,
+                r#"<line x1="{}" y1="{}" x2="{mem_x}" y2="{}" stroke="gray" marker-end="url(#arrow)"/>"#,
+                x + CELL,
+                y + CELL / 2,
+                CELL + slot * CELL + CELL / 2,
+            )
+            .unwrap()
+        }
+        ans.push_str("</svg>\n");
+        ans
+    }
+
+    /// 画出 `data_range` 中每个字节被访问的次数。
+    ///
+    /// `.` 表示未被访问，`#` 表示被访问一次，`2` 到 `9` 表示被访问的次数，`*` 表示更多次。
+    ///
+    /// ```rust
+    /// # use ndarray_layout::{ArrayLayout, Endian::BigEndian};
+    /// let layout = ArrayLayout::<4>::new_contiguous(&[2, 4], BigEndian, 2).slice(1, 0, 2, 2);
+    /// assert_eq!(
+    ///     layout.render_memory_map(2),
+    ///     "\
+    /// bytes 0..14: 8 of 14 touched
+    ///      0: ##..##..##..##
+    /// ",
+    /// );
+    /// assert_eq!(
+    ///     layout.slice(0, 0, 1, 1).broadcast(0, 3).render_memory_map(2),
+    ///     "bytes 0..6: 4 of 6 touched\n     0: 33..33\n",
+    /// );
+    /// ```
+    pub fn render_memory_map(&self, element_size: usize) -> String {
+        if self.num_elements() == 0 {
+            return "empty\n".into();
+        }
+        let range = self.data_range();
+        let start = *range.start();
+        let end = *range.end() + element_size as isize;
+        let mut counts = vec![0usize; (end - start) as usize];
+        for i in 0..self.num_elements() {
+            let offset = (self.element_offset(i, BigEndian) - start) as usize;
+            counts[offset..][..element_size]
+                .iter_mut()
+                .for_each(|c| *c += 1)
+        }
+
+        let touched = counts.iter().filter(|&&c| c > 0).count();
+        let mut ans = format!(
+            "bytes {start}..{end}: {touched} of {} touched\n",
+            counts.len()
+        );
+        for (i, line) in counts.chunks(BYTES_PER_LINE).enumerate() {
+            write!(ans, "{:>6}: ", start + (i * BYTES_PER_LINE) as isize).unwrap();
+            ans.extend(line.iter().map(|&c| match c {
+                0 => '.',
+                1 => '#',
+                2..=9 => (b'0' + c as u8) as char,
+                _ => '*',
+            }));
+            ans.push('\n')
+        }
+        ans
+    }
+}
+
+#[test]
+fn test() {
+    let layout = ArrayLayout::<4>::new(&[2, 2, 3], &[-100, 24, 4], 200).slice(2, 2, -2, 2);
+    assert_eq!(
+        layout.render_ascii(),
+        "\
+[0, .., ..]
++-----+-----+
+| 208 | 200 |
++-----+-----+
+| 232 | 224 |
++-----+-----+
+[1, .., ..]
++-----+-----+
+| 108 | 100 |
++-----+-----+
+| 132 | 124 |
++-----+-----+
+"
+    );
+    assert_eq!(
+        ArrayLayout::<4>::new(&[], &[], 7).render_ascii(),
+        "+---+\n| 7 |\n+---+\n"
+    );
+
+    let map = layout.render_memory_map(4);
+    assert!(map.starts_with("bytes 100..236: 32 of 136 touched\n   100: ####....####"));
+    assert_eq!(map.lines().count(), 6);
+    assert_eq!(
+        ArrayLayout::<4>::new(&[0, 3], &[4, 4], 0).render_memory_map(4),
+        "empty\n"
+    );
+
+    let svg = layout.render_svg(4);
+    assert_eq!(svg.matches("<rect").count(), 8 + 34);
+    assert!(svg.contains(r#"<text x="176" y="84">104</text>"#));
+    assert!(svg.ends_with("</svg>\n"));
+}